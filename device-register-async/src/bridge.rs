@@ -0,0 +1,187 @@
+//! Bridges between the blocking and async interfaces, so one transport implementation serves both trait families
+
+use core::{
+    future::Future,
+    hint,
+    pin::pin,
+    ptr,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use device_register::Register;
+
+use crate::{send::SendRegisterInterface, RegisterInterface};
+
+/// Wrapper making a blocking [`device_register::RegisterInterface`] usable as an async [`RegisterInterface`].
+/// The accesses complete without yielding, so they block the executor for their duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FromBlocking<I> {
+    iface: I,
+}
+
+impl<I> FromBlocking<I> {
+    /// Creates the wrapper of the blocking interface
+    pub const fn new(iface: I) -> Self {
+        Self { iface }
+    }
+
+    /// Returns a reference to the inner interface
+    pub const fn inner(&self) -> &I {
+        &self.iface
+    }
+
+    /// Returns a mutable reference to the inner interface
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iface
+    }
+
+    /// Returns the inner interface
+    pub fn into_inner(self) -> I {
+        self.iface
+    }
+}
+
+impl<I, R, A> RegisterInterface<R, A> for FromBlocking<I>
+where
+    R: Register<Address = A>,
+    I: device_register::RegisterInterface<R, A>,
+{
+    type Error = I::Error;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.iface.read_register()
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.iface.write_register(register)
+    }
+
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.iface.read_fifo_register(values)
+    }
+}
+
+impl<I, R, A> SendRegisterInterface<R, A> for FromBlocking<I>
+where
+    R: Register<Address = A> + Send + Sync,
+    I: device_register::RegisterInterface<R, A> + Send,
+{
+    type Error = I::Error;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.iface.read_register()
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.iface.write_register(register)
+    }
+
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.iface.read_fifo_register(values)
+    }
+}
+
+/// Runs a future to completion on the current thread, e.g. `embassy_futures::block_on` or `futures::executor::block_on`
+pub trait Executor {
+    /// Blocks until the future completes and returns its output
+    fn block_on<F>(&mut self, fut: F) -> F::Output
+    where
+        F: Future;
+}
+
+// Waker doing nothing, like `Waker::noop` which needs a newer compiler than the MSRV
+const NOOP_VTABLE: RawWakerVTable = RawWakerVTable::new(noop_clone, noop, noop, noop);
+
+fn noop_clone(_: *const ()) -> RawWaker {
+    RawWaker::new(ptr::null(), &NOOP_VTABLE)
+}
+
+fn noop(_: *const ()) {}
+
+/// Executor polling the future in a loop until it completes, without sleeping between the polls.
+/// Suited for interfaces whose futures complete without relying on the executor, like busy-polled peripherals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Spin;
+
+impl Executor for Spin {
+    fn block_on<F>(&mut self, fut: F) -> F::Output
+    where
+        F: Future,
+    {
+        let mut fut = pin!(fut);
+        // The waker does nothing since the future is polled again right away
+        #[allow(unsafe_code)]
+        // SAFETY: the functions of the vtable don't use the data pointer
+        let waker = unsafe { Waker::from_raw(noop_clone(ptr::null())) };
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+                return output;
+            }
+            hint::spin_loop();
+        }
+    }
+}
+
+/// Wrapper making an async [`RegisterInterface`] usable as a blocking [`device_register::RegisterInterface`],
+/// each access is run to completion with the executor `X`, [`Spin`] by default.
+/// Note that blocking inside an async executor can deadlock if the access waits for a task of this executor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FromAsync<I, X = Spin> {
+    iface: I,
+    executor: X,
+}
+
+impl<I> FromAsync<I> {
+    /// Creates the wrapper of the async interface, the accesses are polled in a loop by [`Spin`]
+    pub const fn new(iface: I) -> Self {
+        Self {
+            iface,
+            executor: Spin,
+        }
+    }
+}
+
+impl<I, X> FromAsync<I, X> {
+    /// Creates the wrapper of the async interface, the accesses are run by `executor`
+    pub const fn with_executor(iface: I, executor: X) -> Self {
+        Self { iface, executor }
+    }
+
+    /// Returns a reference to the inner interface
+    pub const fn inner(&self) -> &I {
+        &self.iface
+    }
+
+    /// Returns a mutable reference to the inner interface
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iface
+    }
+
+    /// Returns the inner interface and the executor
+    pub fn release(self) -> (I, X) {
+        (self.iface, self.executor)
+    }
+}
+
+impl<I, X, R, A> device_register::RegisterInterface<R, A> for FromAsync<I, X>
+where
+    R: Register<Address = A>,
+    I: RegisterInterface<R, A>,
+    X: Executor,
+{
+    type Error = I::Error;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.executor.block_on(self.iface.read_register())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.executor.block_on(self.iface.write_register(register))
+    }
+
+    fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.executor
+            .block_on(self.iface.read_fifo_register(values))
+    }
+}
@@ -3,8 +3,26 @@
 #![deny(unsafe_code, missing_docs)]
 #![allow(async_fn_in_trait)]
 
+use core::future::Future;
 pub use device_register;
-use device_register::{EditableRegister, ReadableRegister, Register, WritableRegister};
+pub use device_register::{AccessError, InvalidValue, Operation, RegisterError};
+
+mod bridge;
+pub mod send;
+mod timeout;
+#[cfg(feature = "embedded-hal-async")]
+mod wait;
+pub use bridge::{Executor, FromAsync, FromBlocking, Spin};
+pub use timeout::{with_timeout, Elapsed, TimeoutError, TimeoutInterface, Timer};
+#[cfg(feature = "embedded-hal-async")]
+pub use wait::{wait_for, ActiveLevel, WaitError};
+
+use send::{SendRegisterInterface, SendUnlock};
+
+use device_register::{
+    export::NumericAddress, Backoff, Device, EditableRegister, FifoRegister, Key, Paged,
+    ProtectedRegister, ReadableRegister, Register, RetryInterface, Unprotected, WritableRegister,
+};
 
 /// Traits that define how to read and write the registers.
 /// Note that those functions should mostly just be implemented and not used since they are not bound by Read/Write/Edit permission.
@@ -20,6 +38,15 @@ where
 
     /// Writes a register to the device
     async fn write_register(&mut self, register: &R) -> Result<(), Self::Error>;
+
+    /// Reads the register as many times as the length of `values`, used by the FIFO registers.
+    /// Reads each value on its own by default, implement it as one bulk transfer if the device doesn't auto increment the address
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        for value in values {
+            *value = self.read_register().await?;
+        }
+        Ok(())
+    }
 }
 
 /// Trait to safely read a register. Only a readable register can be read.
@@ -63,28 +90,82 @@ where
         for<'w> F: FnOnce(&'w mut R);
 }
 
+/// Extension of [`EditRegister`] to edit a register with an async closure, implemented for all the [`RegisterInterface`].
+/// Requires the `edit-async` feature, since the async closures need rust 1.85.
+#[cfg(feature = "edit-async")]
+pub trait EditRegisterAsync<R, A>: EditRegister<R, A>
+where
+    for<'a> R: EditableRegister<Address = A> + 'a,
+{
+    /// Edit a register with an async closure, which takes the interface and a reference to the register.
+    /// The closure can await other accesses through the interface, like reads of other registers or delays,
+    /// since the interface is held exclusively until the register is written.
+    /// The register is not written if the closure returns an error.
+    async fn edit_async<F>(&mut self, f: F) -> Result<(), <Self as EditRegister<R, A>>::Error>
+    where
+        F: AsyncFnOnce(&mut Self, &mut R) -> Result<(), <Self as EditRegister<R, A>>::Error>;
+}
+
+/// Trait to read the values of a FIFO register. Only a FIFO register can be read this way.
+pub trait ReadFifo<R, A>
+where
+    R: FifoRegister<Address = A>,
+{
+    /// The error type returned by reading the FIFO
+    type Error;
+
+    /// Reads as many values of the FIFO as the length of `values`, in the order they are popped
+    async fn read_fifo(&mut self, values: &mut [R]) -> Result<(), Self::Error>;
+}
+
+/// Trait of the unlock sequence of a protection, performed through the interface `I` before writing a register.
+/// Used by [`WriteRegister`] and [`EditRegister`] so the sequence can't be forgotten.
+pub trait Unlock<I, E> {
+    /// Performs the unlock sequence
+    async fn unlock(iface: &mut I) -> Result<(), E>;
+}
+
+impl<I, E> Unlock<I, E> for Unprotected {
+    async fn unlock(_iface: &mut I) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+impl<I, E, R> Unlock<I, E> for Key<R>
+where
+    R: ProtectedRegister,
+    I: RegisterInterface<R::Unlock, <R::Unlock as Register>::Address, Error = E>,
+{
+    async fn unlock(iface: &mut I) -> Result<(), E> {
+        iface.write_register(&R::key()).await
+    }
+}
+
 impl<I, R, A> ReadRegister<R, A> for I
 where
     for<'a> R: ReadableRegister<Address = A> + 'a,
     I: RegisterInterface<R, A>,
     for<'a> A: 'a,
 {
-    type Error = I::Error;
+    type Error = RegisterError<I::Error>;
 
     async fn read(&mut self) -> Result<R, Self::Error> {
-        self.read_register().await
+        let val = self.read_register().await.map_err(RegisterError::Bus)?;
+        Ok(R::decode(val)?)
     }
 }
 
 impl<I, R, A> WriteRegister<R, A> for I
 where
     for<'a> R: WritableRegister<Address = A> + 'a,
+    R::Protection: Unlock<I, I::Error>,
     I: RegisterInterface<R, A>,
     for<'a> A: 'a,
 {
     type Error = I::Error;
 
     async fn write(&mut self, register: R) -> Result<(), Self::Error> {
+        R::Protection::unlock(self).await?;
         self.write_register(&register).await
     }
 }
@@ -92,17 +173,524 @@ where
 impl<I, R, A> EditRegister<R, A> for I
 where
     for<'a> R: EditableRegister<Address = A> + 'a,
+    R::Protection: Unlock<I, I::Error>,
     I: RegisterInterface<R, A>,
     for<'a> A: 'a,
 {
-    type Error = I::Error;
+    type Error = RegisterError<I::Error>;
 
     async fn edit<F>(&mut self, f: F) -> Result<(), Self::Error>
     where
         for<'w> F: FnOnce(&'w mut R),
     {
-        let mut val = self.read_register().await?;
+        let val = self.read_register().await.map_err(RegisterError::Bus)?;
+        let mut val = R::decode(val)?;
         f(&mut val);
-        self.write_register(&val).await
+        R::Protection::unlock(self)
+            .await
+            .map_err(RegisterError::Bus)?;
+        self.write_register(&val).await.map_err(RegisterError::Bus)
+    }
+}
+
+#[cfg(feature = "edit-async")]
+impl<I, R, A> EditRegisterAsync<R, A> for I
+where
+    for<'a> R: EditableRegister<Address = A> + 'a,
+    R::Protection: Unlock<I, I::Error>,
+    I: RegisterInterface<R, A>,
+    for<'a> A: 'a,
+{
+    async fn edit_async<F>(&mut self, f: F) -> Result<(), RegisterError<I::Error>>
+    where
+        F: AsyncFnOnce(&mut Self, &mut R) -> Result<(), RegisterError<I::Error>>,
+    {
+        let val = self.read_register().await.map_err(RegisterError::Bus)?;
+        let mut val = R::decode(val)?;
+        f(self, &mut val).await?;
+        R::Protection::unlock(self)
+            .await
+            .map_err(RegisterError::Bus)?;
+        self.write_register(&val).await.map_err(RegisterError::Bus)
+    }
+}
+
+impl<I, R, A> ReadFifo<R, A> for I
+where
+    R: FifoRegister<Address = A> + Clone,
+    I: RegisterInterface<R, A>,
+{
+    type Error = RegisterError<I::Error>;
+
+    async fn read_fifo(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.read_fifo_register(values)
+            .await
+            .map_err(RegisterError::Bus)?;
+        for value in values.iter_mut() {
+            *value = R::decode(value.clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// Selects the page of the register `R` if it is on another page than the current one
+async fn select_page<I, S, R>(paged: &mut Paged<I, S>) -> Result<(), I::Error>
+where
+    R: Register,
+    S: Register + From<u8>,
+    I: RegisterInterface<S, S::Address>,
+{
+    match R::PAGE {
+        Some(page) if paged.page() != Some(page) => {
+            // The page is unknown if the write fails midway
+            paged.invalidate();
+            paged.inner_mut().write_register(&S::from(page)).await?;
+            paged.assume_page(page);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+impl<I, S, R, A> RegisterInterface<R, A> for Paged<I, S>
+where
+    R: Register<Address = A>,
+    S: Register + From<u8>,
+    I: RegisterInterface<R, A>,
+    I: RegisterInterface<S, S::Address, Error = <I as RegisterInterface<R, A>>::Error>,
+{
+    type Error = <I as RegisterInterface<R, A>>::Error;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        select_page::<I, S, R>(self).await?;
+        RegisterInterface::<R, A>::read_register(self.inner_mut()).await
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        select_page::<I, S, R>(self).await?;
+        RegisterInterface::<R, A>::write_register(self.inner_mut(), register).await
+    }
+
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        select_page::<I, S, R>(self).await?;
+        RegisterInterface::<R, A>::read_fifo_register(self.inner_mut(), values).await
+    }
+}
+
+/// Selects the page of the register `R` like [`select_page`], through a [`SendRegisterInterface`]
+async fn select_page_send<I, S, R>(paged: &mut Paged<I, S>) -> Result<(), I::Error>
+where
+    R: Register,
+    S: Register + From<u8> + Send + Sync,
+    I: SendRegisterInterface<S, S::Address>,
+{
+    match R::PAGE {
+        Some(page) if paged.page() != Some(page) => {
+            // The page is unknown if the write fails midway
+            paged.invalidate();
+            paged.inner_mut().write_register(&S::from(page)).await?;
+            paged.assume_page(page);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+impl<I, S, R, A> SendRegisterInterface<R, A> for Paged<I, S>
+where
+    R: Register<Address = A> + Send + Sync,
+    S: Register + From<u8> + Send + Sync,
+    I: SendRegisterInterface<R, A>,
+    I: SendRegisterInterface<S, S::Address, Error = <I as SendRegisterInterface<R, A>>::Error>,
+{
+    type Error = <I as SendRegisterInterface<R, A>>::Error;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        select_page_send::<I, S, R>(self).await?;
+        SendRegisterInterface::<R, A>::read_register(self.inner_mut()).await
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        select_page_send::<I, S, R>(self).await?;
+        SendRegisterInterface::<R, A>::write_register(self.inner_mut(), register).await
+    }
+
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        select_page_send::<I, S, R>(self).await?;
+        SendRegisterInterface::<R, A>::read_fifo_register(self.inner_mut(), values).await
+    }
+}
+
+/// Traits that define how to read and write the registers of a device on a bus shared by multiple devices.
+/// Similar to [`RegisterInterface`], but the device is selected on each call using its address on the bus, e.g. its I2C address.
+/// The bus is held across the await points, so it is shared through a mutex, like the embassy-sync `Mutex` with the `embassy-sync` feature.
+pub trait BusInterface<R, A>
+where
+    R: Register<Address = A>,
+{
+    /// The address of a device on the bus
+    type DeviceAddress;
+
+    /// The error type returned by the bus
+    type Error;
+
+    /// Reads a register of the device and returns it
+    async fn read_register(&mut self, device: &Self::DeviceAddress) -> Result<R, Self::Error>;
+
+    /// Writes a register to the device
+    async fn write_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        register: &R,
+    ) -> Result<(), Self::Error>;
+
+    /// Reads the register of the device as many times as the length of `values`, see [`RegisterInterface::read_fifo_register`]
+    async fn read_fifo_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        values: &mut [R],
+    ) -> Result<(), Self::Error> {
+        for value in values {
+            *value = self.read_register(device).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<B, R, A> BusInterface<R, A> for &mut B
+where
+    R: Register<Address = A>,
+    B: BusInterface<R, A>,
+{
+    type DeviceAddress = B::DeviceAddress;
+    type Error = B::Error;
+
+    async fn read_register(&mut self, device: &Self::DeviceAddress) -> Result<R, Self::Error> {
+        (**self).read_register(device).await
+    }
+
+    async fn write_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        register: &R,
+    ) -> Result<(), Self::Error> {
+        (**self).write_register(device, register).await
+    }
+
+    async fn read_fifo_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        values: &mut [R],
+    ) -> Result<(), Self::Error> {
+        (**self).read_fifo_register(device, values).await
+    }
+}
+
+/// The bus is locked for each access, allows sharing it between multiple [`Device`] of different tasks,
+/// like the `SpiDevice` and `I2cDevice` of `embassy-embedded-hal`
+#[cfg(feature = "embassy-sync")]
+impl<M, B, R, A> BusInterface<R, A> for &embassy_sync::mutex::Mutex<M, B>
+where
+    M: embassy_sync::blocking_mutex::raw::RawMutex,
+    R: Register<Address = A>,
+    B: BusInterface<R, A>,
+{
+    type DeviceAddress = B::DeviceAddress;
+    type Error = B::Error;
+
+    async fn read_register(&mut self, device: &Self::DeviceAddress) -> Result<R, Self::Error> {
+        self.lock().await.read_register(device).await
+    }
+
+    async fn write_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        register: &R,
+    ) -> Result<(), Self::Error> {
+        self.lock().await.write_register(device, register).await
+    }
+
+    async fn read_fifo_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        values: &mut [R],
+    ) -> Result<(), Self::Error> {
+        self.lock().await.read_fifo_register(device, values).await
+    }
+}
+
+impl<Bus, Addr, R, A> RegisterInterface<R, A> for Device<Bus, Addr>
+where
+    R: Register<Address = A>,
+    Bus: BusInterface<R, A, DeviceAddress = Addr>,
+{
+    type Error = Bus::Error;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        let (bus, address) = self.parts_mut();
+        bus.read_register(address).await
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let (bus, address) = self.parts_mut();
+        bus.write_register(address, register).await
+    }
+
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        let (bus, address) = self.parts_mut();
+        bus.read_fifo_register(address, values).await
+    }
+}
+
+/// Wrapper of an interface adding the register and the operation to the errors of the accesses, as an [`AccessError`].
+/// Its `read`, `write` and `edit` methods take precedence over the ones of the traits, e.g. `Context::new(iface).read().await`.
+/// The addresses of the registers must implement [`NumericAddress`].
+#[derive(Debug)]
+pub struct Context<I> {
+    iface: I,
+}
+
+impl<I> Context<I> {
+    /// Creates the wrapper of the interface
+    pub const fn new(iface: I) -> Self {
+        Self { iface }
+    }
+
+    /// Returns a reference to the inner interface
+    pub const fn inner(&self) -> &I {
+        &self.iface
+    }
+
+    /// Returns a mutable reference to the inner interface
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iface
+    }
+
+    /// Returns the inner interface
+    pub fn into_inner(self) -> I {
+        self.iface
+    }
+
+    /// Reads a register, see [`ReadRegister::read`]
+    pub async fn read<R, A>(&mut self) -> Result<R, AccessError<<I as ReadRegister<R, A>>::Error>>
+    where
+        R: ReadableRegister<Address = A>,
+        A: NumericAddress,
+        I: ReadRegister<R, A>,
+    {
+        self.iface
+            .read()
+            .await
+            .map_err(|err| AccessError::new::<R>(Operation::Read, err))
+    }
+
+    /// Writes a register, see [`WriteRegister::write`]
+    pub async fn write<R, A>(&mut self, register: R) -> Result<(), AccessError<I::Error>>
+    where
+        R: WritableRegister<Address = A>,
+        A: NumericAddress,
+        I: WriteRegister<R, A>,
+    {
+        self.iface
+            .write(register)
+            .await
+            .map_err(|err| AccessError::new::<R>(Operation::Write, err))
+    }
+
+    /// Edits a register, see [`EditRegister::edit`]
+    pub async fn edit<R, A, F>(&mut self, f: F) -> Result<(), AccessError<I::Error>>
+    where
+        for<'a> R: EditableRegister<Address = A> + 'a,
+        A: NumericAddress,
+        I: EditRegister<R, A>,
+        for<'w> F: FnOnce(&'w mut R),
+    {
+        self.iface
+            .edit(f)
+            .await
+            .map_err(|err| AccessError::new::<R>(Operation::Edit, err))
+    }
+}
+
+/// Policy deciding if a failed access is retried.
+/// Implemented for a `u32`, the number of retries of any error,
+/// for closures `FnMut(attempt, &error) -> bool` and for [`Backoff`] with an async delay provider, e.g. `|us| Timer::after_micros(us.into())`.
+/// The future is `Send`, so the [`RetryInterface`] of a [`SendRegisterInterface`] is one too.
+pub trait RetryPolicy<E> {
+    /// Called after each failed `attempt`, starting at 1.
+    /// Returns true to retry the access, after waiting the backoff if any
+    fn retry(&mut self, attempt: u32, error: &E) -> impl Future<Output = bool> + Send;
+}
+
+impl<E> RetryPolicy<E> for u32 {
+    fn retry(&mut self, attempt: u32, _error: &E) -> impl Future<Output = bool> + Send {
+        core::future::ready(attempt <= *self)
+    }
+}
+
+impl<E, F> RetryPolicy<E> for F
+where
+    F: FnMut(u32, &E) -> bool,
+{
+    fn retry(&mut self, attempt: u32, error: &E) -> impl Future<Output = bool> + Send {
+        core::future::ready(self(attempt, error))
+    }
+}
+
+impl<E, D, F, Fut> RetryPolicy<E> for Backoff<D, F>
+where
+    D: FnMut(u32) -> Fut,
+    Fut: Future<Output = ()> + Send,
+    F: FnMut(&E) -> bool,
+{
+    fn retry(&mut self, attempt: u32, error: &E) -> impl Future<Output = bool> + Send {
+        // The error is only borrowed by the predicate, so the future doesn't hold it
+        let backoff = self.backoff_us(attempt);
+        let delay = match self.should_retry(attempt, error) {
+            true => Some((self.delay_mut())(backoff)),
+            false => None,
+        };
+        async move {
+            match delay {
+                Some(delay) => {
+                    delay.await;
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+}
+
+impl<I, P, R, A> RegisterInterface<R, A> for RetryInterface<I, P>
+where
+    R: Register<Address = A>,
+    R::Protection: Unlock<I, I::Error>,
+    I: RegisterInterface<R, A>,
+    P: RetryPolicy<I::Error>,
+{
+    type Error = I::Error;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        let (iface, policy) = self.parts_mut();
+        let mut attempt = 0;
+        loop {
+            match iface.read_register().await {
+                Ok(register) => return Ok(register),
+                Err(err) => {
+                    attempt += 1;
+                    if !policy.retry(attempt, &err).await {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let (iface, policy) = self.parts_mut();
+        // The register is unlocked by the caller before the first attempt, the retries unlock it again
+        let mut attempt = 0;
+        loop {
+            let result = match attempt {
+                0 => iface.write_register(register).await,
+                _ => match R::Protection::unlock(iface).await {
+                    Ok(()) => iface.write_register(register).await,
+                    Err(err) => Err(err),
+                },
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    attempt += 1;
+                    if !policy.retry(attempt, &err).await {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        let (iface, policy) = self.parts_mut();
+        let mut attempt = 0;
+        loop {
+            match iface.read_fifo_register(values).await {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    attempt += 1;
+                    if !policy.retry(attempt, &err).await {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<I, P, R, A> SendRegisterInterface<R, A> for RetryInterface<I, P>
+where
+    R: Register<Address = A> + Send + Sync,
+    R::Protection: SendUnlock<I, I::Error>,
+    I: SendRegisterInterface<R, A>,
+    I::Error: Send,
+    P: RetryPolicy<I::Error> + Send,
+{
+    type Error = I::Error;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        let (iface, policy) = self.parts_mut();
+        let mut attempt = 0;
+        loop {
+            match iface.read_register().await {
+                Ok(register) => return Ok(register),
+                Err(err) => {
+                    attempt += 1;
+                    if !policy.retry(attempt, &err).await {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let (iface, policy) = self.parts_mut();
+        // The register is unlocked by the caller before the first attempt, the retries unlock it again
+        let mut attempt = 0;
+        loop {
+            let result = match attempt {
+                0 => iface.write_register(register).await,
+                _ => match R::Protection::unlock(iface).await {
+                    Ok(()) => iface.write_register(register).await,
+                    Err(err) => Err(err),
+                },
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    attempt += 1;
+                    if !policy.retry(attempt, &err).await {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        let (iface, policy) = self.parts_mut();
+        let mut attempt = 0;
+        loop {
+            match iface.read_fifo_register(values).await {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    attempt += 1;
+                    if !policy.retry(attempt, &err).await {
+                        return Err(err);
+                    }
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,193 @@
+//! Variants of the traits whose futures are `Send`, so the drivers can be spawned on multithreaded executors like tokio.
+//!
+//! The futures of the `async fn` of the other traits can't be proven `Send` for a generic interface.
+//! Implement [`SendRegisterInterface`] instead of [`RegisterInterface`](crate::RegisterInterface) to use these traits,
+//! e.g. with `async fn` in the implementation, which the compiler checks to be `Send`.
+
+use core::future::Future;
+
+use device_register::{
+    EditableRegister, FifoRegister, Key, ProtectedRegister, ReadableRegister, Register,
+    RegisterError, Unprotected, WritableRegister,
+};
+
+/// Variant of [`RegisterInterface`](crate::RegisterInterface) whose futures are `Send`
+pub trait SendRegisterInterface<R, A>: Send
+where
+    R: Register<Address = A>,
+{
+    /// The error type returned by the interface
+    type Error;
+
+    /// Reads a register and returns it
+    fn read_register(&mut self) -> impl Future<Output = Result<R, Self::Error>> + Send;
+
+    /// Writes a register to the device
+    fn write_register(
+        &mut self,
+        register: &R,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Reads the register as many times as the length of `values`, see [`RegisterInterface::read_fifo_register`](crate::RegisterInterface::read_fifo_register)
+    fn read_fifo_register(
+        &mut self,
+        values: &mut [R],
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send
+    where
+        R: Send,
+    {
+        async move {
+            for value in values {
+                *value = self.read_register().await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Variant of [`ReadRegister`](crate::ReadRegister) whose future is `Send`
+pub trait SendReadRegister<R, A>
+where
+    R: ReadableRegister<Address = A>,
+{
+    /// The error type returned by reading a register
+    type Error;
+
+    /// Read a register
+    fn read(&mut self) -> impl Future<Output = Result<R, Self::Error>> + Send;
+}
+
+/// Variant of [`WriteRegister`](crate::WriteRegister) whose future is `Send`
+pub trait SendWriteRegister<R, A>
+where
+    R: WritableRegister<Address = A>,
+{
+    /// The error type returned by writing a register
+    type Error;
+
+    /// Write a register
+    fn write(&mut self, register: R) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+/// Variant of [`EditRegister`](crate::EditRegister) whose future is `Send`
+pub trait SendEditRegister<R, A>
+where
+    R: EditableRegister<Address = A>,
+{
+    /// The error type returned by editing a register
+    type Error;
+
+    /// Edit a register. The closure takes a reference to the register,
+    /// the same register must be edited, then returned.
+    fn edit<F>(&mut self, f: F) -> impl Future<Output = Result<(), Self::Error>> + Send
+    where
+        for<'w> F: FnOnce(&'w mut R) + Send;
+}
+
+/// Variant of [`ReadFifo`](crate::ReadFifo) whose future is `Send`
+pub trait SendReadFifo<R, A>
+where
+    R: FifoRegister<Address = A>,
+{
+    /// The error type returned by reading the FIFO
+    type Error;
+
+    /// Reads as many values of the FIFO as the length of `values`, in the order they are popped
+    fn read_fifo(
+        &mut self,
+        values: &mut [R],
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+/// Variant of [`Unlock`](crate::Unlock) whose future is `Send`
+pub trait SendUnlock<I, E> {
+    /// Performs the unlock sequence
+    fn unlock(iface: &mut I) -> impl Future<Output = Result<(), E>> + Send;
+}
+
+impl<I, E> SendUnlock<I, E> for Unprotected
+where
+    I: Send,
+{
+    async fn unlock(_iface: &mut I) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+impl<I, E, R> SendUnlock<I, E> for Key<R>
+where
+    R: ProtectedRegister,
+    R::Unlock: Send + Sync,
+    I: SendRegisterInterface<R::Unlock, <R::Unlock as Register>::Address, Error = E>,
+{
+    async fn unlock(iface: &mut I) -> Result<(), E> {
+        iface.write_register(&R::key()).await
+    }
+}
+
+impl<I, R, A> SendReadRegister<R, A> for I
+where
+    R: ReadableRegister<Address = A> + Send,
+    I: SendRegisterInterface<R, A>,
+{
+    type Error = RegisterError<I::Error>;
+
+    async fn read(&mut self) -> Result<R, Self::Error> {
+        let val = self.read_register().await.map_err(RegisterError::Bus)?;
+        Ok(R::decode(val)?)
+    }
+}
+
+impl<I, R, A> SendReadFifo<R, A> for I
+where
+    R: FifoRegister<Address = A> + Clone + Send,
+    I: SendRegisterInterface<R, A>,
+{
+    type Error = RegisterError<I::Error>;
+
+    async fn read_fifo(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.read_fifo_register(values)
+            .await
+            .map_err(RegisterError::Bus)?;
+        for value in values.iter_mut() {
+            *value = R::decode(value.clone())?;
+        }
+        Ok(())
+    }
+}
+
+impl<I, R, A> SendWriteRegister<R, A> for I
+where
+    R: WritableRegister<Address = A> + Send + Sync,
+    R::Protection: SendUnlock<I, I::Error>,
+    I: SendRegisterInterface<R, A>,
+{
+    type Error = I::Error;
+
+    async fn write(&mut self, register: R) -> Result<(), Self::Error> {
+        R::Protection::unlock(self).await?;
+        self.write_register(&register).await
+    }
+}
+
+impl<I, R, A> SendEditRegister<R, A> for I
+where
+    R: EditableRegister<Address = A> + Send + Sync,
+    R::Protection: SendUnlock<I, I::Error>,
+    I: SendRegisterInterface<R, A>,
+{
+    type Error = RegisterError<I::Error>;
+
+    async fn edit<F>(&mut self, f: F) -> Result<(), Self::Error>
+    where
+        for<'w> F: FnOnce(&'w mut R) + Send,
+    {
+        let val = self.read_register().await.map_err(RegisterError::Bus)?;
+        let mut val = R::decode(val)?;
+        f(&mut val);
+        R::Protection::unlock(self)
+            .await
+            .map_err(RegisterError::Bus)?;
+        self.write_register(&val).await.map_err(RegisterError::Bus)
+    }
+}
@@ -0,0 +1,190 @@
+//! Timeouts of the accesses, so a stalled bus transaction doesn't hang the driver forever
+
+use core::{
+    fmt,
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
+
+use device_register::Register;
+
+use crate::{send::SendRegisterInterface, RegisterInterface};
+
+/// Timer used to limit the duration of the accesses.
+/// Implemented for closures returning a sleep future, e.g. `|duration| embassy_time::Timer::after(duration)`.
+/// The future is `Send`, so the [`TimeoutInterface`] of a [`SendRegisterInterface`] is one too.
+pub trait Timer<D> {
+    /// Waits for `duration`
+    fn sleep(&mut self, duration: D) -> impl Future<Output = ()> + Send;
+}
+
+impl<D, F, Fut> Timer<D> for F
+where
+    F: FnMut(D) -> Fut,
+    Fut: Future<Output = ()> + Send,
+{
+    fn sleep(&mut self, duration: D) -> impl Future<Output = ()> + Send {
+        self(duration)
+    }
+}
+
+/// Error returned by [`with_timeout`] when the duration elapsed before the completion of the future
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Elapsed;
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("timeout elapsed")
+    }
+}
+
+/// Races the future against the timer, returns [`Elapsed`] if the timer completes first.
+/// The future is dropped on a timeout, so a bus transaction can be interrupted midway.
+pub async fn with_timeout<T, D, F>(timer: &mut T, duration: D, fut: F) -> Result<F::Output, Elapsed>
+where
+    T: Timer<D>,
+    F: Future,
+{
+    let mut fut = pin!(fut);
+    let mut sleep = pin!(timer.sleep(duration));
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = fut.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(Elapsed)),
+            Poll::Pending => Poll::Pending,
+        }
+    })
+    .await
+}
+
+/// Error of an access through a [`TimeoutInterface`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutError<E> {
+    /// The access did not complete before the timeout
+    Timeout,
+
+    /// Error returned by the interface
+    Interface(E),
+}
+
+impl<E> From<Elapsed> for TimeoutError<E> {
+    fn from(_: Elapsed) -> Self {
+        TimeoutError::Timeout
+    }
+}
+
+impl<E> fmt::Display for TimeoutError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeoutError::Timeout => f.write_str("timeout elapsed"),
+            TimeoutError::Interface(err) => write!(f, "interface error: {}", err),
+        }
+    }
+}
+
+/// Wrapper of an interface failing the reads and writes that take longer than the timeout with [`TimeoutError::Timeout`]
+#[derive(Debug)]
+pub struct TimeoutInterface<I, T, D> {
+    iface: I,
+    timer: T,
+    timeout: D,
+}
+
+impl<I, T, D> TimeoutInterface<I, T, D> {
+    /// Creates the wrapper of the interface, each access is limited to `timeout` using the timer
+    pub const fn new(iface: I, timer: T, timeout: D) -> Self {
+        Self {
+            iface,
+            timer,
+            timeout,
+        }
+    }
+
+    /// Returns a reference to the inner interface
+    pub const fn inner(&self) -> &I {
+        &self.iface
+    }
+
+    /// Returns a mutable reference to the inner interface
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iface
+    }
+
+    /// Sets the timeout of the accesses
+    pub fn set_timeout(&mut self, timeout: D) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the inner interface and the timer
+    pub fn release(self) -> (I, T) {
+        (self.iface, self.timer)
+    }
+}
+
+impl<I, T, D, R, A> RegisterInterface<R, A> for TimeoutInterface<I, T, D>
+where
+    R: Register<Address = A>,
+    I: RegisterInterface<R, A>,
+    T: Timer<D>,
+    D: Clone,
+{
+    type Error = TimeoutError<I::Error>;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        let read = self.iface.read_register();
+        with_timeout(&mut self.timer, self.timeout.clone(), read)
+            .await?
+            .map_err(TimeoutError::Interface)
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let write = self.iface.write_register(register);
+        with_timeout(&mut self.timer, self.timeout.clone(), write)
+            .await?
+            .map_err(TimeoutError::Interface)
+    }
+
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        let read = self.iface.read_fifo_register(values);
+        with_timeout(&mut self.timer, self.timeout.clone(), read)
+            .await?
+            .map_err(TimeoutError::Interface)
+    }
+}
+
+impl<I, T, D, R, A> SendRegisterInterface<R, A> for TimeoutInterface<I, T, D>
+where
+    R: Register<Address = A> + Send + Sync,
+    I: SendRegisterInterface<R, A>,
+    T: Timer<D> + Send,
+    D: Clone + Send + Sync,
+{
+    type Error = TimeoutError<I::Error>;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        let read = self.iface.read_register();
+        with_timeout(&mut self.timer, self.timeout.clone(), read)
+            .await?
+            .map_err(TimeoutError::Interface)
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let write = self.iface.write_register(register);
+        with_timeout(&mut self.timer, self.timeout.clone(), write)
+            .await?
+            .map_err(TimeoutError::Interface)
+    }
+
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        let read = self.iface.read_fifo_register(values);
+        with_timeout(&mut self.timer, self.timeout.clone(), read)
+            .await?
+            .map_err(TimeoutError::Interface)
+    }
+}
@@ -0,0 +1,70 @@
+//! Interrupt driven waits on the value of a register, instead of polling it
+
+use core::fmt;
+
+use device_register::{ReadableRegister, Register};
+use embedded_hal_async::digital::Wait;
+
+use crate::ReadRegister;
+
+/// Error of [`wait_for`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitError<E, P> {
+    /// Error returned by reading the register
+    Register(E),
+
+    /// Error returned by waiting on the interrupt pin
+    Pin(P),
+}
+
+impl<E, P> fmt::Display for WaitError<E, P>
+where
+    E: fmt::Display,
+    P: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Register(err) => write!(f, "register error: {}", err),
+            WaitError::Pin(err) => write!(f, "interrupt pin error: {:?}", err),
+        }
+    }
+}
+
+/// The level of the interrupt pin while the interrupt is pending
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActiveLevel {
+    /// The pin is high while the interrupt is pending
+    High,
+
+    /// The pin is low while the interrupt is pending
+    Low,
+}
+
+/// Waits until the register `R` satisfies the predicate, then returns it.
+/// The register is read once before waiting, then again each time the interrupt pin is at its active level, until the predicate holds.
+/// Waiting on the level instead of an edge doesn't miss an interrupt raised between the read and the wait,
+/// but the device must deassert the pin when the register is read, or the register is polled while the pin stays active.
+pub async fn wait_for<R, I, P, F>(
+    iface: &mut I,
+    pin: &mut P,
+    level: ActiveLevel,
+    mut predicate: F,
+) -> Result<R, WaitError<I::Error, P::Error>>
+where
+    R: ReadableRegister,
+    I: ReadRegister<R, <R as Register>::Address>,
+    P: Wait,
+    F: FnMut(&R) -> bool,
+{
+    loop {
+        let register = iface.read().await.map_err(WaitError::Register)?;
+        if predicate(&register) {
+            return Ok(register);
+        }
+        match level {
+            ActiveLevel::High => pin.wait_for_high().await,
+            ActiveLevel::Low => pin.wait_for_low().await,
+        }
+        .map_err(WaitError::Pin)?;
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use device_register::{RWRegister, Register};
+use device_register_async::*;
+
+// Blocking and async mocks of the same device
+#[derive(Default)]
+pub struct BlockingBus {
+    pub registers: HashMap<u8, u16>,
+}
+
+impl<R> device_register::RegisterInterface<R, u8> for BlockingBus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.registers
+            .get(&R::ADDRESS)
+            .map(|v| (*v).into())
+            .ok_or(())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.registers.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct AsyncBus {
+    pub registers: HashMap<u8, u16>,
+}
+
+impl<R> RegisterInterface<R, u8> for AsyncBus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        tokio::task::yield_now().await;
+        self.registers
+            .get(&R::ADDRESS)
+            .map(|v| (*v).into())
+            .ok_or(())
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        tokio::task::yield_now().await;
+        self.registers.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x01)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[tokio::test]
+async fn from_blocking() {
+    let mut device = FromBlocking::new(BlockingBus::default());
+    device.write(Config(0x42)).await.unwrap();
+    device.edit(|r: &mut Config| r.0 += 1).await.unwrap();
+
+    let config: Config = device.read().await.unwrap();
+    assert_eq!(config.0, 0x43);
+}
+
+#[test]
+fn from_async() {
+    use device_register::{EditRegister, ReadRegister, WriteRegister};
+
+    let mut device = FromAsync::new(AsyncBus::default());
+    device.write(Config(0x42)).unwrap();
+    device.edit(|r: &mut Config| r.0 += 1).unwrap();
+
+    let config: Config = device.read().unwrap();
+    assert_eq!(config.0, 0x43);
+    assert_eq!(device.inner().registers[&0x01], 0x43);
+}
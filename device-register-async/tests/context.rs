@@ -0,0 +1,63 @@
+mod common;
+
+use common::{DeviceDriver, DeviceError};
+use device_register::{AccessError, Operation, RWRegister, Register, RegisterError};
+use device_register_async::*;
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = "common::REGISTER1")]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = DeviceError;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        let bytes = self.registers.get(&R::ADDRESS).ok_or(DeviceError::Get)?;
+        let reg = u16::from_be_bytes(*bytes);
+        Ok(reg.into())
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let bytes: u16 = register.clone().into();
+        self.registers.insert(R::ADDRESS, bytes.to_be_bytes());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn access_error() {
+    let mut device = Context::new(DeviceDriver::new());
+    device.inner_mut().registers.clear();
+
+    let err = device.read::<Config, _>().await.unwrap_err();
+    assert_eq!(
+        err,
+        AccessError {
+            register: "Config",
+            address: 0x01,
+            operation: Operation::Read,
+            error: RegisterError::Bus(DeviceError::Get),
+        }
+    );
+    let err = device.edit(|r: &mut Config| r.0 += 1).await.unwrap_err();
+    assert_eq!(err.operation, Operation::Edit);
+
+    device.write(Config(0x42)).await.unwrap();
+    device.edit(|r: &mut Config| r.0 += 1).await.unwrap();
+    let config: Config = device.read().await.unwrap();
+    assert_eq!(config.0, 0x43);
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use device_register::{RORegister, RWRegister, Register};
+use device_register_async::*;
+
+// Mock of a device with the registers and a log of the writes
+#[derive(Default)]
+pub struct DeviceDriver {
+    pub registers: HashMap<u8, u16>,
+    pub writes: Vec<(u8, u16)>,
+}
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        tokio::task::yield_now().await;
+        self.registers
+            .get(&R::ADDRESS)
+            .map(|v| (*v).into())
+            .ok_or(())
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        tokio::task::yield_now().await;
+        let val = register.clone().into();
+        self.registers.insert(R::ADDRESS, val);
+        self.writes.push((R::ADDRESS, val));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = 0x01)]
+pub struct Status(pub u16);
+impl From<Status> for u16 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Status {
+    fn from(val: u16) -> Self {
+        Status(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x02)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[tokio::test]
+async fn edit_with_other_reads() {
+    let mut device = DeviceDriver::default();
+    device.registers.insert(0x01, 0x10);
+    device.registers.insert(0x02, 0x01);
+
+    device
+        .edit_async(async |device, config: &mut Config| {
+            let status: Status = device.read().await?;
+            tokio::task::yield_now().await;
+            config.0 += status.0;
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(device.writes, [(0x02, 0x11)]);
+}
+
+#[tokio::test]
+async fn closure_error_skips_write() {
+    let mut device = DeviceDriver::default();
+    device.registers.insert(0x02, 0x01);
+
+    let res = device
+        .edit_async(async |device, config: &mut Config| {
+            // The status register is missing from the mock
+            let status: Status = device.read().await?;
+            config.0 = status.0;
+            Ok(())
+        })
+        .await;
+
+    assert_eq!(res.unwrap_err(), RegisterError::Bus(()));
+    assert!(device.writes.is_empty());
+}
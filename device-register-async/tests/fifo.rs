@@ -0,0 +1,59 @@
+use std::collections::{HashMap, VecDeque};
+
+use device_register::{RORegister, Register};
+use device_register_async::*;
+
+// Mock of a device reading the FIFOs one value at a time
+#[derive(Default)]
+pub struct DeviceDriver {
+    pub fifos: HashMap<u8, VecDeque<u16>>,
+    pub transfers: usize,
+}
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        tokio::task::yield_now().await;
+        self.transfers += 1;
+        let fifo = self.fifos.get_mut(&R::ADDRESS).ok_or(())?;
+        fifo.pop_front().map(Into::into).ok_or(())
+    }
+
+    async fn write_register(&mut self, _register: &R) -> Result<(), Self::Error> {
+        Err(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RORegister)]
+#[register(addr = 0x3F, fifo)]
+pub struct Sample(pub u16);
+impl From<Sample> for u16 {
+    fn from(val: Sample) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Sample {
+    fn from(val: u16) -> Self {
+        Sample(val)
+    }
+}
+
+#[tokio::test]
+async fn read_fifo() {
+    let mut device = DeviceDriver::default();
+    device.fifos.insert(0x3F, VecDeque::from([1, 2, 3]));
+
+    let mut samples = [Sample(0); 3];
+    device.read_fifo(&mut samples).await.unwrap();
+    assert_eq!(samples, [Sample(1), Sample(2), Sample(3)]);
+    assert_eq!(device.transfers, 3);
+
+    // The FIFO is empty
+    let res = device.read_fifo(&mut samples[..1]).await;
+    assert_eq!(res.unwrap_err(), RegisterError::Bus(()));
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use device_register::{maybe_async, RWRegister, Register};
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x01)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+// The delay of embedded-hal-async is replaced by the one of embedded-hal in the blocking module
+#[maybe_async(blocking = "blocking")]
+pub mod asynch {
+    use super::Config;
+    use device_register_async::{RegisterInterface, WriteRegister};
+    use embedded_hal_async::delay::DelayNs;
+
+    pub struct Driver<I, D> {
+        pub iface: I,
+        pub delay: D,
+    }
+
+    impl<I, D> Driver<I, D>
+    where
+        I: RegisterInterface<Config, u8>,
+        D: DelayNs,
+    {
+        pub async fn reset(&mut self) -> Result<(), I::Error> {
+            self.iface.write(Config(0x8000)).await?;
+            self.delay.delay_ms(2).await;
+            Ok(())
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Bus {
+    pub registers: HashMap<u8, u16>,
+}
+
+impl<R> device_register::RegisterInterface<R, u8> for Bus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.registers
+            .get(&R::ADDRESS)
+            .map(|v| (*v).into())
+            .ok_or(())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.registers.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+}
+
+// Delay counting the elapsed time instead of waiting
+#[derive(Default)]
+pub struct Delay {
+    pub elapsed_ns: u64,
+}
+
+impl embedded_hal::delay::DelayNs for Delay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.elapsed_ns += u64::from(ns);
+    }
+}
+
+impl embedded_hal_async::delay::DelayNs for Delay {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.elapsed_ns += u64::from(ns);
+    }
+}
+
+#[tokio::test]
+async fn maybe_async_hal() {
+    let mut driver = blocking::Driver {
+        iface: Bus::default(),
+        delay: Delay::default(),
+    };
+    driver.reset().unwrap();
+    assert_eq!(driver.iface.registers[&0x01], 0x8000);
+    assert_eq!(driver.delay.elapsed_ns, 2_000_000);
+
+    let mut driver = asynch::Driver {
+        iface: device_register_async::FromBlocking::new(Bus::default()),
+        delay: Delay::default(),
+    };
+    driver.reset().await.unwrap();
+    assert_eq!(driver.iface.inner().registers[&0x01], 0x8000);
+    assert_eq!(driver.delay.elapsed_ns, 2_000_000);
+}
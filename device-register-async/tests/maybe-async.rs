@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use device_register::{maybe_async, RWRegister, Register};
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x01)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+// The driver is written once, and used with both the blocking and the async interfaces
+#[maybe_async(blocking = "blocking")]
+pub mod asynch {
+    use super::Config;
+    use device_register_async::{EditRegister, ReadRegister, RegisterError, RegisterInterface};
+
+    pub struct Driver<I> {
+        pub iface: I,
+    }
+
+    impl<I> Driver<I>
+    where
+        I: RegisterInterface<Config, u8>,
+    {
+        pub async fn increment(&mut self) -> Result<u16, RegisterError<I::Error>> {
+            self.iface.edit(|r: &mut Config| r.0 += 1).await?;
+            self.value().await
+        }
+
+        // Mapped to the output of the future in the blocking module
+        pub fn value(
+            &mut self,
+        ) -> impl core::future::Future<Output = Result<u16, RegisterError<I::Error>>> + '_ {
+            let iface = &mut self.iface;
+            async move {
+                let config: Config = iface.read().await?;
+                Ok(config.0)
+            }
+        }
+
+        pub async fn scaled(&mut self, scale: u16) -> Result<u16, RegisterError<I::Error>> {
+            let scale = async |value: u16| value * scale;
+            Ok(scale(self.value().await?).await)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Bus {
+    pub registers: HashMap<u8, u16>,
+}
+
+impl<R> device_register::RegisterInterface<R, u8> for Bus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.registers
+            .get(&R::ADDRESS)
+            .map(|v| (*v).into())
+            .ok_or(())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.registers.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn maybe_async() {
+    let mut bus = Bus::default();
+    bus.registers.insert(0x01, 0x42);
+
+    let mut driver = blocking::Driver { iface: bus };
+    assert_eq!(driver.increment(), Ok(0x43));
+    assert_eq!(driver.scaled(2), Ok(0x86));
+
+    let iface = device_register_async::FromBlocking::new(driver.iface);
+    let mut driver = asynch::Driver { iface };
+    assert_eq!(driver.increment().await, Ok(0x44));
+    assert_eq!(driver.scaled(2).await, Ok(0x88));
+}
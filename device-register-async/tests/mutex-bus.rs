@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use device_register::{Device, RWRegister, Register};
+use device_register_async::*;
+use embassy_sync::{blocking_mutex::raw::NoopRawMutex, mutex::Mutex};
+
+// Mock of a bus with multiple devices, each selected by its address
+#[derive(Default)]
+pub struct Bus {
+    pub registers: HashMap<(u8, u8), u16>,
+}
+
+impl<R> BusInterface<R, u8> for Bus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type DeviceAddress = u8;
+    type Error = ();
+
+    async fn read_register(&mut self, device: &u8) -> Result<R, Self::Error> {
+        // Lets the other device access the bus between the accesses
+        tokio::task::yield_now().await;
+        let value = self.registers.get(&(*device, R::ADDRESS)).ok_or(())?;
+        Ok((*value).into())
+    }
+
+    async fn write_register(&mut self, device: &u8, register: &R) -> Result<(), Self::Error> {
+        tokio::task::yield_now().await;
+        self.registers
+            .insert((*device, R::ADDRESS), register.clone().into());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x01)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[tokio::test]
+async fn mutex_bus() {
+    let bus = Mutex::<NoopRawMutex, _>::new(Bus::default());
+    let mut sensor1 = Device::new(&bus, 0x48);
+    let mut sensor2 = Device::new(&bus, 0x49);
+
+    // Both devices access the bus concurrently
+    let (res1, res2) = tokio::join!(sensor1.write(Config(0x42)), sensor2.write(Config(0x45)));
+    res1.unwrap();
+    res2.unwrap();
+    let (res1, res2) = tokio::join!(
+        sensor1.edit(|r: &mut Config| r.0 += 1),
+        sensor2.edit(|r: &mut Config| r.0 += 2)
+    );
+    res1.unwrap();
+    res2.unwrap();
+
+    let registers = &bus.lock().await.registers;
+    assert_eq!(registers.get(&(0x48, 0x01)), Some(&0x43));
+    assert_eq!(registers.get(&(0x49, 0x01)), Some(&0x47));
+}
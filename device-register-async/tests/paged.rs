@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use device_register::{Paged, RWRegister, Register, WORegister};
+use device_register_async::*;
+
+const PAGE_SELECT: u8 = 0x7F;
+
+// Mock of a device where the registers depend on the selected page
+#[derive(Default)]
+pub struct PagedDevice {
+    pub page: u8,
+    pub selects: usize,
+    pub registers: HashMap<(u8, u8), u16>,
+}
+
+impl<R> RegisterInterface<R, u8> for PagedDevice
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        let value = self.registers.get(&(self.page, R::ADDRESS)).ok_or(())?;
+        Ok((*value).into())
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let value: u16 = register.clone().into();
+        if R::ADDRESS == PAGE_SELECT {
+            self.page = value as u8;
+            self.selects += 1;
+        } else {
+            self.registers.insert((self.page, R::ADDRESS), value);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = "PAGE_SELECT")]
+pub struct PageSelect(pub u16);
+impl From<PageSelect> for u16 {
+    fn from(val: PageSelect) -> Self {
+        val.0
+    }
+}
+impl From<u16> for PageSelect {
+    fn from(val: u16) -> Self {
+        PageSelect(val)
+    }
+}
+impl From<u8> for PageSelect {
+    fn from(page: u8) -> Self {
+        PageSelect(page.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x10, page = 0)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x10, page = 2)]
+pub struct Threshold(pub u16);
+impl From<Threshold> for u16 {
+    fn from(val: Threshold) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Threshold {
+    fn from(val: u16) -> Self {
+        Threshold(val)
+    }
+}
+
+#[tokio::test]
+async fn paged_registers() {
+    let mut device = Paged::<_, PageSelect>::new(PagedDevice::default());
+    device.write(Config(0x42)).await.unwrap();
+    device.write(Threshold(0x45)).await.unwrap();
+    device.edit(|r: &mut Threshold| r.0 += 1).await.unwrap();
+    assert_eq!(device.page(), Some(2));
+
+    let config: Config = device.read().await.unwrap();
+    assert_eq!(config.0, 0x42);
+
+    let device = device.into_inner();
+    assert_eq!(device.selects, 3);
+    assert_eq!(device.registers.get(&(0, 0x10)), Some(&0x42));
+    assert_eq!(device.registers.get(&(2, 0x10)), Some(&0x46));
+}
@@ -0,0 +1,66 @@
+use device_register::{RWRegister, Register, WORegister};
+use device_register_async::*;
+
+// Mock of a device that logs the writes
+#[derive(Default)]
+pub struct DeviceDriver {
+    pub writes: Vec<(u8, u8)>,
+}
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u8>,
+    u8: From<R>,
+{
+    type Error = ();
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        Ok(0.into())
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.writes.push((R::ADDRESS, register.clone().into()));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = 0x7E)]
+pub struct UnlockReg(pub u8);
+impl From<UnlockReg> for u8 {
+    fn from(val: UnlockReg) -> Self {
+        val.0
+    }
+}
+impl From<u8> for UnlockReg {
+    fn from(val: u8) -> Self {
+        UnlockReg(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x10, protected(by = "UnlockReg", key = 0xA5))]
+pub struct Calibration(pub u8);
+impl From<Calibration> for u8 {
+    fn from(val: Calibration) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Calibration {
+    fn from(val: u8) -> Self {
+        Calibration(val)
+    }
+}
+
+#[tokio::test]
+async fn protected_registers() {
+    let mut device = DeviceDriver::default();
+
+    device.write(Calibration(0x42)).await.unwrap();
+    device.edit(|r: &mut Calibration| r.0 += 1).await.unwrap();
+
+    assert_eq!(
+        device.writes,
+        [(0x7E, 0xA5), (0x10, 0x42), (0x7E, 0xA5), (0x10, 0x01)]
+    );
+}
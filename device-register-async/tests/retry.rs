@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use device_register::{
+    Backoff, RORegister, RWRegister, Register, RegisterError, RetryInterface, WORegister,
+};
+use device_register_async::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    Nack,
+    Timeout,
+}
+
+// Mock of a bus failing the next accesses with the errors queued
+#[derive(Default)]
+pub struct FlakyBus {
+    pub registers: HashMap<u8, u16>,
+    pub errors: Vec<Error>,
+    pub accesses: usize,
+}
+
+impl FlakyBus {
+    fn access(&mut self) -> Result<(), Error> {
+        self.accesses += 1;
+        match self.errors.pop() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<R> RegisterInterface<R, u8> for FlakyBus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = Error;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.access()?;
+        Ok(self.registers[&R::ADDRESS].into())
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.access()?;
+        self.registers.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+
+    // The FIFO is read in a single transaction
+    async fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.access()?;
+        for value in values {
+            *value = self.registers[&R::ADDRESS].into();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x01)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RORegister)]
+#[register(addr = 0x3F, fifo)]
+pub struct Sample(pub u16);
+impl From<Sample> for u16 {
+    fn from(val: Sample) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Sample {
+    fn from(val: u16) -> Self {
+        Sample(val)
+    }
+}
+
+#[tokio::test]
+async fn retry() {
+    let bus = FlakyBus {
+        errors: vec![Error::Nack, Error::Nack],
+        ..Default::default()
+    };
+    let mut device = RetryInterface::new(bus, 2);
+    device.write(Config(0x42)).await.unwrap();
+    assert_eq!(device.inner().accesses, 3);
+
+    let mut delays = Vec::new();
+    let policy = Backoff::new(
+        5,
+        100,
+        |us| {
+            delays.push(us);
+            tokio::task::yield_now()
+        },
+        |err: &Error| *err == Error::Nack,
+    );
+    let (mut bus, _) = device.release();
+    bus.errors = vec![Error::Timeout, Error::Nack];
+    let mut device = RetryInterface::new(bus, policy);
+
+    // The timeout is not retried
+    let res = device.edit(|r: &mut Config| r.0 += 1).await;
+    assert_eq!(res, Err(RegisterError::Bus(Error::Timeout)));
+    let (bus, _) = device.release();
+    assert_eq!(bus.accesses, 5);
+    assert_eq!(delays, [100]);
+}
+
+#[tokio::test]
+async fn retry_fifo_burst() {
+    let bus = FlakyBus {
+        registers: HashMap::from([(0x3F, 0x42)]),
+        errors: vec![Error::Nack, Error::Nack],
+        ..Default::default()
+    };
+    let mut device = RetryInterface::new(bus, 2);
+
+    // The burst is retried as a whole, one transaction per attempt
+    let mut samples = [Sample(0); 4];
+    device.read_fifo(&mut samples).await.unwrap();
+    assert_eq!(samples, [Sample(0x42); 4]);
+    assert_eq!(device.inner().accesses, 3);
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = 0x10)]
+pub struct UnlockReg(pub u16);
+impl From<UnlockReg> for u16 {
+    fn from(val: UnlockReg) -> Self {
+        val.0
+    }
+}
+impl From<u16> for UnlockReg {
+    fn from(val: u16) -> Self {
+        UnlockReg(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = 0x11, protected(by = "UnlockReg", key = 0xA5))]
+pub struct Calibration(pub u16);
+impl From<Calibration> for u16 {
+    fn from(val: Calibration) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Calibration {
+    fn from(val: u16) -> Self {
+        Calibration(val)
+    }
+}
+
+// Mock of a device consuming the key on the next write, even if the write fails
+#[derive(Default)]
+pub struct LockedBus {
+    pub bus: FlakyBus,
+    pub unlocked: bool,
+}
+
+impl<R> RegisterInterface<R, u8> for LockedBus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = Error;
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.bus.read_register().await
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let value: u16 = register.clone().into();
+        if R::ADDRESS == UnlockReg::ADDRESS {
+            self.unlocked = value == 0xA5;
+            return Ok(());
+        }
+        let unlocked = std::mem::take(&mut self.unlocked);
+        self.bus.write_register(register).await?;
+        match unlocked {
+            true => Ok(()),
+            false => Err(Error::Timeout),
+        }
+    }
+}
+
+#[tokio::test]
+async fn retry_protected_write() {
+    let bus = LockedBus {
+        bus: FlakyBus {
+            errors: vec![Error::Nack],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut device = RetryInterface::new(bus, 1);
+
+    // The failed write consumed the key, the retry unlocks the register again
+    device.write(Calibration(0x42)).await.unwrap();
+    assert_eq!(device.inner().bus.registers.get(&0x11), Some(&0x42));
+    assert_eq!(device.inner().bus.accesses, 2);
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use device_register::{Paged, RORegister, RWRegister, Register, RetryInterface, WORegister};
+use device_register_async::{
+    send::{
+        SendEditRegister, SendReadFifo, SendReadRegister, SendRegisterInterface, SendWriteRegister,
+    },
+    FromBlocking, TimeoutInterface,
+};
+
+// Mock of a device whose futures are Send, with a log of the writes
+#[derive(Default)]
+pub struct DeviceDriver {
+    pub registers: HashMap<u8, u8>,
+    pub writes: Vec<(u8, u8)>,
+}
+
+impl<R> SendRegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u8> + Sync,
+    u8: From<R>,
+{
+    type Error = ();
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        tokio::task::yield_now().await;
+        Ok(self.registers.get(&R::ADDRESS).copied().unwrap_or(0).into())
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        tokio::task::yield_now().await;
+        let val = register.clone().into();
+        self.registers.insert(R::ADDRESS, val);
+        self.writes.push((R::ADDRESS, val));
+        Ok(())
+    }
+}
+
+// Mock of a blocking device, wrapped to be spawned
+#[derive(Default)]
+pub struct BlockingDriver {
+    pub registers: HashMap<u8, u8>,
+}
+
+impl<R> device_register::RegisterInterface<R, u8> for BlockingDriver
+where
+    R: Register<Address = u8> + Clone + From<u8>,
+    u8: From<R>,
+{
+    type Error = ();
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        Ok(self.registers.get(&R::ADDRESS).copied().unwrap_or(0).into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.registers.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = 0x7F)]
+pub struct PageSelect(pub u8);
+impl From<PageSelect> for u8 {
+    fn from(val: PageSelect) -> Self {
+        val.0
+    }
+}
+impl From<u8> for PageSelect {
+    fn from(val: u8) -> Self {
+        PageSelect(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = 0x7E)]
+pub struct UnlockReg(pub u8);
+impl From<UnlockReg> for u8 {
+    fn from(val: UnlockReg) -> Self {
+        val.0
+    }
+}
+impl From<u8> for UnlockReg {
+    fn from(val: u8) -> Self {
+        UnlockReg(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x10, protected(by = "UnlockReg", key = 0xA5))]
+pub struct Calibration(pub u8);
+impl From<Calibration> for u8 {
+    fn from(val: Calibration) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Calibration {
+    fn from(val: u8) -> Self {
+        Calibration(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RORegister)]
+#[register(addr = 0x3F, fifo)]
+pub struct Sample(pub u8);
+impl From<Sample> for u8 {
+    fn from(val: Sample) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Sample {
+    fn from(val: u8) -> Self {
+        Sample(val)
+    }
+}
+
+#[tokio::test]
+async fn spawn_driver_task() {
+    let task = tokio::spawn(async move {
+        let mut device = DeviceDriver::default();
+        device.write(Calibration(0x42)).await.unwrap();
+        device.edit(|r: &mut Calibration| r.0 += 1).await.unwrap();
+        let calibration: Calibration = device.read().await.unwrap();
+        (calibration.0, device.writes)
+    });
+
+    let (calibration, writes) = task.await.unwrap();
+    assert_eq!(calibration, 0x43);
+    assert_eq!(
+        writes,
+        [(0x7E, 0xA5), (0x10, 0x42), (0x7E, 0xA5), (0x10, 0x43)]
+    );
+}
+
+#[tokio::test]
+async fn spawn_fifo_task() {
+    let task = tokio::spawn(async move {
+        let mut device = DeviceDriver::default();
+        device.registers.insert(0x3F, 0x07);
+        let mut samples = [Sample(0); 3];
+        device.read_fifo(&mut samples).await.unwrap();
+        samples
+    });
+
+    assert_eq!(task.await.unwrap(), [Sample(0x07); 3]);
+}
+
+#[tokio::test]
+async fn spawn_wrapped_driver_task() {
+    let task = tokio::spawn(async move {
+        // The timer never elapses, the accesses complete right away
+        let timer = |_: u32| core::future::pending::<()>();
+        let iface = TimeoutInterface::new(FromBlocking::new(BlockingDriver::default()), timer, 10);
+        let mut device = Paged::<_, PageSelect>::new(RetryInterface::new(iface, 2));
+        device.write(Calibration(0x42)).await.unwrap();
+        device.edit(|r: &mut Calibration| r.0 += 1).await.unwrap();
+        let calibration: Calibration = device.read().await.unwrap();
+        calibration.0
+    });
+
+    assert_eq!(task.await.unwrap(), 0x43);
+}
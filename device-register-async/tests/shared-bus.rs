@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use device_register::{Device, RWRegister, Register};
+use device_register_async::*;
+
+// Mock of a bus with multiple devices, each selected by its address
+#[derive(Default)]
+pub struct Bus {
+    pub registers: HashMap<(u8, u8), u16>,
+}
+
+impl<R> BusInterface<R, u8> for Bus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type DeviceAddress = u8;
+    type Error = ();
+
+    async fn read_register(&mut self, device: &u8) -> Result<R, Self::Error> {
+        let value = self.registers.get(&(*device, R::ADDRESS)).ok_or(())?;
+        Ok((*value).into())
+    }
+
+    async fn write_register(&mut self, device: &u8, register: &R) -> Result<(), Self::Error> {
+        self.registers
+            .insert((*device, R::ADDRESS), register.clone().into());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x01)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[tokio::test]
+async fn shared_bus() {
+    let mut bus = Bus::default();
+
+    Device::new(&mut bus, 0x48)
+        .write(Config(0x42))
+        .await
+        .unwrap();
+    Device::new(&mut bus, 0x49)
+        .write(Config(0x45))
+        .await
+        .unwrap();
+
+    let mut sensor1 = Device::new(bus, 0x48);
+    sensor1.edit(|r: &mut Config| r.0 += 1).await.unwrap();
+    let config: Config = sensor1.read().await.unwrap();
+    assert_eq!(config.0, 0x43);
+
+    let (bus, _) = sensor1.release();
+    assert_eq!(bus.registers.get(&(0x49, 0x01)), Some(&0x45));
+}
@@ -0,0 +1,74 @@
+use std::{collections::HashMap, future::pending};
+
+use device_register::{RWRegister, Register};
+use device_register_async::*;
+
+// Mock of a bus where the stalled registers never complete
+#[derive(Default)]
+pub struct StallingBus {
+    pub registers: HashMap<u8, u16>,
+    pub stalled: bool,
+}
+
+impl<R> RegisterInterface<R, u8> for StallingBus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        if self.stalled {
+            pending::<()>().await;
+        }
+        Ok(self.registers[&R::ADDRESS].into())
+    }
+
+    async fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        if self.stalled {
+            pending::<()>().await;
+        }
+        self.registers.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x01)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[tokio::test]
+async fn timeout() {
+    // The timer completes after yielding once to the executor
+    let timer = |_ticks: u32| tokio::task::yield_now();
+    let mut device = TimeoutInterface::new(StallingBus::default(), timer, 1);
+
+    device.write(Config(0x42)).await.unwrap();
+    let config: Config = device.read().await.unwrap();
+    assert_eq!(config.0, 0x42);
+
+    device.inner_mut().stalled = true;
+    let res = device.write(Config(0x45)).await;
+    assert_eq!(res, Err(TimeoutError::Timeout));
+    assert_eq!(device.inner().registers[&0x01], 0x42);
+}
+
+#[tokio::test]
+async fn elapsed() {
+    let mut timer = |_ticks: u32| tokio::task::yield_now();
+    assert_eq!(with_timeout(&mut timer, 1, async { 42 }).await, Ok(42));
+    assert_eq!(
+        with_timeout(&mut timer, 1, pending::<()>()).await,
+        Err(Elapsed)
+    );
+}
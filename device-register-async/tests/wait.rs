@@ -0,0 +1,176 @@
+use std::{cell::RefCell, rc::Rc};
+
+use device_register::{RORegister, Register};
+use device_register_async::*;
+use embedded_hal::digital::{ErrorKind, ErrorType};
+use embedded_hal_async::digital::Wait;
+
+// State of a device with a status register and an interrupt line, active high
+#[derive(Default)]
+pub struct State {
+    pub status: u16,
+    pub line: bool,
+    // Statuses raised while the pin is waited, each one asserts the line
+    pub events: Vec<u16>,
+    // Status raised right after the next read, before the pin is waited
+    pub raised_after_read: Option<u16>,
+}
+
+// Mock of a device whose status is read to clear the interrupt line
+#[derive(Default)]
+pub struct DeviceDriver {
+    pub state: Rc<RefCell<State>>,
+    pub reads: usize,
+}
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + From<u16>,
+{
+    type Error = ();
+
+    async fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.reads += 1;
+        let mut state = self.state.borrow_mut();
+        let status = state.status;
+        state.line = false;
+        if let Some(raised) = state.raised_after_read.take() {
+            state.status = raised;
+            state.line = true;
+        }
+        Ok(status.into())
+    }
+
+    async fn write_register(&mut self, _register: &R) -> Result<(), Self::Error> {
+        Err(())
+    }
+}
+
+// Mock of the interrupt pin, waiting raises the next event if the line is not asserted
+pub struct InterruptPin {
+    pub state: Rc<RefCell<State>>,
+}
+
+impl InterruptPin {
+    async fn wait_for_event(&mut self) -> Result<(), ErrorKind> {
+        tokio::task::yield_now().await;
+        let mut state = self.state.borrow_mut();
+        if state.events.is_empty() {
+            return Err(ErrorKind::Other);
+        }
+        state.status = state.events.remove(0);
+        state.line = true;
+        Ok(())
+    }
+}
+
+impl ErrorType for InterruptPin {
+    type Error = ErrorKind;
+}
+
+impl Wait for InterruptPin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        if self.state.borrow().line {
+            return Ok(());
+        }
+        self.wait_for_event().await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        Err(ErrorKind::Other)
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_event().await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        Err(ErrorKind::Other)
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_event().await
+    }
+}
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = 0x01)]
+pub struct Status(pub u16);
+impl From<Status> for u16 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Status {
+    fn from(val: u16) -> Self {
+        Status(val)
+    }
+}
+
+fn device(state: State) -> (DeviceDriver, InterruptPin) {
+    let state = Rc::new(RefCell::new(state));
+    let pin = InterruptPin {
+        state: state.clone(),
+    };
+    (DeviceDriver { state, reads: 0 }, pin)
+}
+
+#[tokio::test]
+async fn wait_for_ready() {
+    let (mut device, mut pin) = device(State {
+        events: vec![0x01, 0x03, 0x07],
+        ..State::default()
+    });
+
+    let status = wait_for(
+        &mut device,
+        &mut pin,
+        ActiveLevel::High,
+        |status: &Status| status.0 & 0x02 != 0,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(status.0, 0x03);
+    assert_eq!(device.reads, 3);
+    assert_eq!(device.state.borrow().events, [0x07]);
+}
+
+#[tokio::test]
+async fn line_asserted_before_wait() {
+    // The interrupt is raised between the first read and the wait, an edge would be missed
+    let (mut device, mut pin) = device(State {
+        raised_after_read: Some(0x03),
+        ..State::default()
+    });
+
+    let status = wait_for(
+        &mut device,
+        &mut pin,
+        ActiveLevel::High,
+        |status: &Status| status.0 & 0x02 != 0,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(status.0, 0x03);
+    assert_eq!(device.reads, 2);
+}
+
+#[tokio::test]
+async fn pin_error() {
+    let (mut device, mut pin) = device(State {
+        events: vec![0x01],
+        ..State::default()
+    });
+
+    let res = wait_for(
+        &mut device,
+        &mut pin,
+        ActiveLevel::High,
+        |status: &Status| status.0 & 0x02 != 0,
+    )
+    .await;
+    assert_eq!(res.unwrap_err(), WaitError::Pin(ErrorKind::Other));
+    assert_eq!(device.reads, 2);
+}
@@ -0,0 +1,533 @@
+#![doc = include_str!("../README.md")]
+#![deny(unsafe_code, missing_docs)]
+
+use std::{
+    env, fmt,
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(any(feature = "yaml", feature = "toml"))]
+pub mod map;
+#[cfg(feature = "svd")]
+pub mod svd;
+
+/// Description of a device, the input of the generator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    /// The name of the device
+    pub name: String,
+
+    /// The type of the address of the registers, e.g. `u32`
+    pub address_type: String,
+
+    /// The peripherals of the device, each is generated in its own module
+    pub peripherals: Vec<Peripheral>,
+}
+
+/// Description of a peripheral, a group of registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Peripheral {
+    /// The name of the peripheral
+    pub name: String,
+
+    /// The description of the peripheral
+    pub description: Option<String>,
+
+    /// The address of the peripheral, added to the offset of each register.
+    /// `None` when the registers are addressed directly, e.g. the registers of an I2C sensor
+    pub base_address: Option<u64>,
+
+    /// The registers of the peripheral
+    pub registers: Vec<RegisterDescription>,
+}
+
+/// Description of a register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterDescription {
+    /// The name of the register
+    pub name: String,
+
+    /// The description of the register
+    pub description: Option<String>,
+
+    /// The offset of the register from the base address of the peripheral
+    pub offset: u64,
+
+    /// The size of the register in bits
+    pub size: u32,
+
+    /// The permission of the register
+    pub access: Access,
+
+    /// The value of the register after a reset
+    pub reset: Option<u64>,
+
+    /// If reading the register modifies the state of the device
+    pub read_side_effects: bool,
+
+    /// The fields of the register
+    pub fields: Vec<FieldDescription>,
+}
+
+/// Description of a field of a register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDescription {
+    /// The name of the field
+    pub name: String,
+
+    /// The description of the field
+    pub description: Option<String>,
+
+    /// The position of the least significant bit of the field
+    pub offset: u32,
+
+    /// The number of bits of the field
+    pub width: u32,
+
+    /// The permission of the field
+    pub access: Access,
+}
+
+/// The permission of a register, maps to the derive used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Read only, `RORegister`
+    ReadOnly,
+
+    /// Write only, `WORegister`
+    WriteOnly,
+
+    /// Edit only, `EORegister`
+    EditOnly,
+
+    /// Read and edit, `RERegister`
+    ReadEdit,
+
+    /// Read, write and edit, `RWRegister`
+    ReadWrite,
+}
+
+impl Access {
+    /// Returns the derive used for this access
+    pub fn derive(self) -> &'static str {
+        match self {
+            Access::ReadOnly => "RORegister",
+            Access::WriteOnly => "WORegister",
+            Access::EditOnly => "EORegister",
+            Access::ReadEdit => "RERegister",
+            Access::ReadWrite => "RWRegister",
+        }
+    }
+
+    /// Returns the short name of the access, as used by the `fields` attribute and the register maps
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Access::ReadOnly => "ro",
+            Access::WriteOnly => "wo",
+            Access::EditOnly => "eo",
+            Access::ReadEdit => "re",
+            Access::ReadWrite => "rw",
+        }
+    }
+
+    /// Returns true if the value can be read
+    pub fn is_readable(self) -> bool {
+        !matches!(self, Access::WriteOnly)
+    }
+
+    /// Returns true if the value can be modified
+    pub fn is_writable(self) -> bool {
+        !matches!(self, Access::ReadOnly)
+    }
+}
+
+/// Errors that can occur while generating the registers
+#[derive(Debug)]
+pub enum Error {
+    /// The description could not be read or the output could not be written
+    Io(io::Error),
+
+    /// The description is malformed
+    Parse(String),
+
+    /// The description is valid but contains something that can't be generated
+    Invalid(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "io error: {}", err),
+            Error::Parse(msg) => write!(f, "parse error: {}", msg),
+            Error::Invalid(msg) => write!(f, "invalid description: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// Generates the rust code of the registers of the device
+pub fn generate(device: &Device) -> Result<String, Error> {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Registers of {}, generated by device-register-codegen. Do not edit.",
+        device.name
+    )
+    .unwrap();
+    for peripheral in &device.peripherals {
+        generate_peripheral(&mut out, device, peripheral)?;
+    }
+    Ok(out)
+}
+
+fn generate_peripheral(
+    out: &mut String,
+    device: &Device,
+    peripheral: &Peripheral,
+) -> Result<(), Error> {
+    writeln!(out).unwrap();
+    write_doc(out, "", peripheral.description.as_deref(), &peripheral.name);
+    writeln!(out, "pub mod {} {{", snake_case(&peripheral.name)).unwrap();
+    if let Some(base_address) = peripheral.base_address {
+        writeln!(out, "    /// Base address of the peripheral").unwrap();
+        writeln!(
+            out,
+            "    pub const BASE_ADDRESS: {} = {:#x};",
+            device.address_type, base_address
+        )
+        .unwrap();
+    }
+
+    for register in &peripheral.registers {
+        generate_register(out, device, peripheral, register)?;
+    }
+
+    let raw = peripheral
+        .registers
+        .first()
+        .map(|register| raw_type(register))
+        .transpose()?;
+    let same_size = peripheral
+        .registers
+        .windows(2)
+        .all(|regs| regs[0].size == regs[1].size);
+    if let (Some(raw), true) = (raw, same_size) {
+        let registers: Vec<_> = peripheral
+            .registers
+            .iter()
+            .map(|register| camel_case(&register.name))
+            .collect();
+        writeln!(out).unwrap();
+        writeln!(out, "    /// All the registers of the peripheral").unwrap();
+        writeln!(out, "    #[derive(device_register::RegisterMap)]").unwrap();
+        writeln!(
+            out,
+            "    #[register_map(ty = \"{}\", raw = \"{}\", registers({}))]",
+            device.address_type,
+            raw,
+            registers.join(", ")
+        )
+        .unwrap();
+        writeln!(out, "    pub struct Map;").unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+    Ok(())
+}
+
+fn generate_register(
+    out: &mut String,
+    device: &Device,
+    peripheral: &Peripheral,
+    register: &RegisterDescription,
+) -> Result<(), Error> {
+    let name = camel_case(&register.name);
+    let raw = raw_type(register)?;
+    let address = peripheral.base_address.unwrap_or(0) + register.offset;
+    let side_effects = if register.read_side_effects {
+        ", read_side_effects"
+    } else {
+        ""
+    };
+
+    // Items of the module are separated by an empty line
+    if !out.ends_with("{\n") {
+        writeln!(out).unwrap();
+    }
+    write_doc(out, "    ", register.description.as_deref(), &register.name);
+    writeln!(
+        out,
+        "    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::{})]",
+        register.access.derive()
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    #[register(addr = \"{:#x}\", ty = \"{}\", raw = \"{}\"{})]",
+        address, device.address_type, raw, side_effects
+    )
+    .unwrap();
+    if let Some(reset) = register.reset {
+        writeln!(out, "    #[register(reset = \"{:#x}\")]", reset).unwrap();
+    }
+    if !register.fields.is_empty() {
+        writeln!(out, "    #[register(fields(").unwrap();
+        for field in &register.fields {
+            write!(
+                out,
+                "        {}(offset = {}, width = {}",
+                field.name, field.offset, field.width
+            )
+            .unwrap();
+            if field.access != register.access {
+                write!(out, ", access = \"{}\"", field.access.short_name()).unwrap();
+            }
+            if let Some(description) = &field.description {
+                write!(out, ", doc = {:?}", description).unwrap();
+            }
+            writeln!(out, "),").unwrap();
+        }
+        writeln!(out, "    ))]").unwrap();
+    }
+    writeln!(out, "    pub struct {}(pub {});", name, raw).unwrap();
+
+    if register.reset.is_some() || !register.fields.is_empty() {
+        writeln!(out).unwrap();
+        writeln!(out, "    impl {} {{", name).unwrap();
+        if let Some(reset) = register.reset {
+            writeln!(out, "        /// Value of the register after a reset").unwrap();
+            writeln!(
+                out,
+                "        pub const RESET_VALUE: {} = {:#x};",
+                raw, reset
+            )
+            .unwrap();
+        }
+        for field in &register.fields {
+            generate_field(out, register, raw, field)?;
+        }
+        writeln!(out, "    }}").unwrap();
+    }
+
+    if register.reset.is_some() {
+        writeln!(out).unwrap();
+        writeln!(out, "    impl Default for {} {{", name).unwrap();
+        writeln!(out, "        fn default() -> Self {{").unwrap();
+        writeln!(out, "            Self(Self::RESET_VALUE)").unwrap();
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+
+    writeln!(out).unwrap();
+    writeln!(out, "    impl From<{}> for {} {{", raw, name).unwrap();
+    writeln!(out, "        fn from(value: {}) -> Self {{", raw).unwrap();
+    writeln!(out, "            Self(value)").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "    impl From<{}> for {} {{", name, raw).unwrap();
+    writeln!(out, "        fn from(value: {}) -> Self {{", name).unwrap();
+    writeln!(out, "            value.0").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    Ok(())
+}
+
+fn generate_field(
+    out: &mut String,
+    register: &RegisterDescription,
+    raw: &str,
+    field: &FieldDescription,
+) -> Result<(), Error> {
+    if field.width == 0 || field.offset + field.width > register.size {
+        return Err(Error::Invalid(format!(
+            "field {} does not fit in register {}",
+            field.name, register.name
+        )));
+    }
+    let getter = snake_case(&field.name);
+    let mask = if field.width == 64 {
+        u64::MAX
+    } else {
+        (1 << field.width) - 1
+    };
+    let shifted = |value: &str| match field.offset {
+        0 => value.to_string(),
+        offset => format!("({} >> {})", value, offset),
+    };
+
+    if field.access.is_readable() {
+        writeln!(out).unwrap();
+        write_doc(out, "        ", field.description.as_deref(), &field.name);
+        if field.width == 1 {
+            writeln!(out, "        pub const fn {}(&self) -> bool {{", getter).unwrap();
+            writeln!(out, "            {} & 0x1 != 0", shifted("self.0")).unwrap();
+        } else if field.width == register.size {
+            writeln!(out, "        pub const fn {}(&self) -> {} {{", getter, raw).unwrap();
+            writeln!(out, "            self.0").unwrap();
+        } else {
+            writeln!(out, "        pub const fn {}(&self) -> {} {{", getter, raw).unwrap();
+            writeln!(out, "            {} & {:#x}", shifted("self.0"), mask).unwrap();
+        }
+        writeln!(out, "        }}").unwrap();
+    }
+
+    if field.access.is_writable() {
+        let setter = format!("set_{}", getter.trim_start_matches("r#"));
+        let (ty, value) = if field.width == 1 {
+            ("bool", format!("{}::from(value)", raw))
+        } else {
+            (raw, format!("(value & {:#x})", mask))
+        };
+        let value = match field.offset {
+            0 => value,
+            offset => format!("({} << {})", value, offset),
+        };
+        writeln!(out).unwrap();
+        write_doc(out, "        ", field.description.as_deref(), &field.name);
+        writeln!(
+            out,
+            "        pub fn {}(&mut self, value: {}) {{",
+            setter, ty
+        )
+        .unwrap();
+        if field.width == register.size {
+            writeln!(out, "            self.0 = value;").unwrap();
+        } else {
+            let mask = match field.offset {
+                0 => format!("{:#x}", mask),
+                offset => format!("({:#x} << {})", mask, offset),
+            };
+            writeln!(
+                out,
+                "            self.0 = (self.0 & !{}) | {};",
+                mask, value
+            )
+            .unwrap();
+        }
+        writeln!(out, "        }}").unwrap();
+    }
+    Ok(())
+}
+
+fn write_doc(out: &mut String, indent: &str, description: Option<&str>, name: &str) {
+    let description = description.unwrap_or(name);
+    for line in description.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        writeln!(out, "{}/// {}", indent, line).unwrap();
+    }
+}
+
+fn raw_type(register: &RegisterDescription) -> Result<&'static str, Error> {
+    match register.size {
+        8 => Ok("u8"),
+        16 => Ok("u16"),
+        32 => Ok("u32"),
+        64 => Ok("u64"),
+        size => Err(Error::Invalid(format!(
+            "register {} has an unsupported size of {} bits",
+            register.name, size
+        ))),
+    }
+}
+
+/// Splits a name in words, on underscores, spaces and lower to upper case transitions
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && previous_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Converts a name to a valid `CamelCase` rust identifier
+fn camel_case(name: &str) -> String {
+    let ident: String = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first
+                .into_iter()
+                .chain(chars.map(|c| c.to_ascii_lowercase()))
+                .collect::<String>()
+        })
+        .collect();
+    escape(ident)
+}
+
+/// Converts a name to a valid `snake_case` rust identifier
+fn snake_case(name: &str) -> String {
+    let ident = words(name)
+        .iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    escape(ident)
+}
+
+fn escape(ident: String) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+        "where", "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+        "try", "typeof", "unsized", "virtual", "yield",
+    ];
+    if ident.starts_with(|c: char| c.is_ascii_digit()) || ident.is_empty() {
+        format!("_{}", ident)
+    } else if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{}", ident)
+    } else {
+        ident
+    }
+}
+
+/// Writes the generated code in the `OUT_DIR` of the build script.
+/// Meant to be called from a `build.rs`, `input` is used to rerun the build script when it changes.
+pub fn write_out_dir(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    code: &str,
+) -> Result<PathBuf, Error> {
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+        Error::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            "OUT_DIR is not set, the generator must be called from a build script",
+        ))
+    })?;
+    let path = Path::new(&out_dir).join(output);
+    fs::write(&path, code)?;
+    println!("cargo:rerun-if-changed={}", input.as_ref().display());
+    Ok(path)
+}
@@ -0,0 +1,193 @@
+//! Import of the registers from a simple YAML or TOML register map.
+//!
+//! Meant for devices that don't ship an SVD file, like external I2C or SPI sensors.
+//! The map describes a single device, all the registers are generated in a module named after the device.
+//!
+//! ```yaml
+//! name: TMP117
+//! address_type: u8 # Optional, defaults to u8
+//! size: 16 # Optional default size of the registers in bits, defaults to 8
+//! registers:
+//!   - name: CONFIGURATION
+//!     description: Configuration register
+//!     address: 0x01
+//!     access: rw # One of ro, wo, eo, re or rw, defaults to rw
+//!     reset: 0x0220
+//!     fields:
+//!       - name: MOD
+//!         offset: 10
+//!         width: 2 # Optional, defaults to 1
+//!   - name: TEMP_RESULT
+//!     address: 0x00
+//!     access: ro
+//!     read_side_effects: false # Optional, defaults to false
+//! ```
+
+use std::{ffi::OsStr, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::{Access, Device, Error, FieldDescription, Peripheral, RegisterDescription};
+
+/// The root of a register map
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Map {
+    name: String,
+    description: Option<String>,
+    #[serde(default = "default_address_type")]
+    address_type: String,
+    #[serde(default = "default_size")]
+    size: u32,
+    registers: Vec<Register>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Register {
+    name: String,
+    description: Option<String>,
+    address: u64,
+    size: Option<u32>,
+    #[serde(default = "default_access")]
+    access: MapAccess,
+    reset: Option<u64>,
+    #[serde(default)]
+    read_side_effects: bool,
+    #[serde(default)]
+    fields: Vec<Field>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Field {
+    name: String,
+    description: Option<String>,
+    offset: u32,
+    #[serde(default = "default_width")]
+    width: u32,
+    access: Option<MapAccess>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+enum MapAccess {
+    #[serde(rename = "ro", alias = "read-only")]
+    ReadOnly,
+    #[serde(rename = "wo", alias = "write-only")]
+    WriteOnly,
+    #[serde(rename = "eo", alias = "edit-only")]
+    EditOnly,
+    #[serde(rename = "re", alias = "read-edit")]
+    ReadEdit,
+    #[serde(rename = "rw", alias = "read-write")]
+    ReadWrite,
+}
+
+impl From<MapAccess> for Access {
+    fn from(access: MapAccess) -> Self {
+        match access {
+            MapAccess::ReadOnly => Access::ReadOnly,
+            MapAccess::WriteOnly => Access::WriteOnly,
+            MapAccess::EditOnly => Access::EditOnly,
+            MapAccess::ReadEdit => Access::ReadEdit,
+            MapAccess::ReadWrite => Access::ReadWrite,
+        }
+    }
+}
+
+fn default_address_type() -> String {
+    String::from("u8")
+}
+
+fn default_size() -> u32 {
+    8
+}
+
+fn default_width() -> u32 {
+    1
+}
+
+fn default_access() -> MapAccess {
+    MapAccess::ReadWrite
+}
+
+impl From<Map> for Device {
+    fn from(map: Map) -> Self {
+        let size = map.size;
+        let registers = map
+            .registers
+            .into_iter()
+            .map(|register| {
+                let access = register.access.into();
+                RegisterDescription {
+                    name: register.name,
+                    description: register.description,
+                    offset: register.address,
+                    size: register.size.unwrap_or(size),
+                    access,
+                    reset: register.reset,
+                    read_side_effects: register.read_side_effects,
+                    fields: register
+                        .fields
+                        .into_iter()
+                        .map(|field| FieldDescription {
+                            name: field.name,
+                            description: field.description,
+                            offset: field.offset,
+                            width: field.width,
+                            access: field.access.map_or(access, Access::from),
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Device {
+            name: map.name.clone(),
+            address_type: map.address_type,
+            peripherals: vec![Peripheral {
+                name: map.name,
+                description: map.description,
+                base_address: None,
+                registers,
+            }],
+        }
+    }
+}
+
+/// Parses a YAML register map as a device
+#[cfg(feature = "yaml")]
+pub fn parse_yaml(yaml: &str) -> Result<Device, Error> {
+    let map: Map = serde_yaml::from_str(yaml).map_err(|err| Error::Parse(err.to_string()))?;
+    Ok(map.into())
+}
+
+/// Parses a TOML register map as a device
+#[cfg(feature = "toml")]
+pub fn parse_toml(toml: &str) -> Result<Device, Error> {
+    let map: Map = toml::from_str(toml).map_err(|err| Error::Parse(err.to_string()))?;
+    Ok(map.into())
+}
+
+/// Parses a register map and writes the generated registers in the `OUT_DIR`.
+/// The format is selected using the extension of the file, `.yaml`, `.yml` or `.toml`.
+/// Meant to be called from a `build.rs`, the generated file can then be included using `include!`.
+pub fn build(map: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<(), Error> {
+    let path = map.as_ref();
+    let content = fs::read_to_string(path)?;
+    let device = match path.extension().and_then(OsStr::to_str) {
+        #[cfg(feature = "yaml")]
+        Some("yaml" | "yml") => parse_yaml(&content)?,
+        #[cfg(feature = "toml")]
+        Some("toml") => parse_toml(&content)?,
+        _ => {
+            return Err(Error::Invalid(format!(
+                "unsupported register map format {}",
+                path.display()
+            )))
+        }
+    };
+    let code = crate::generate(&device)?;
+    crate::write_out_dir(path, output, &code)?;
+    Ok(())
+}
@@ -0,0 +1,274 @@
+//! Import of the registers from CMSIS-SVD files.
+//!
+//! Supports the peripherals, including the ones derived from another peripheral, the registers, including arrays of registers,
+//! and the fields of the registers. Clusters are not supported and are ignored.
+
+use std::{fs, path::Path};
+
+use roxmltree::{Document, Node};
+
+use crate::{Access, Device, Error, FieldDescription, Peripheral, RegisterDescription};
+
+/// Register properties that are inherited from the device and the peripheral
+#[derive(Debug, Clone, Copy)]
+struct Properties {
+    size: u32,
+    access: Access,
+    reset: Option<u64>,
+}
+
+/// Parses the content of an SVD file as a device
+pub fn parse(svd: &str) -> Result<Device, Error> {
+    let doc = Document::parse(svd).map_err(|err| Error::Parse(err.to_string()))?;
+    let root = doc.root_element();
+    if !root.has_tag_name("device") {
+        return Err(Error::Parse(String::from(
+            "the root element must be <device>",
+        )));
+    }
+
+    let defaults = Properties {
+        size: 32,
+        access: Access::ReadWrite,
+        reset: None,
+    };
+    let properties = properties(root, defaults)?;
+    let mut peripherals: Vec<Peripheral> = Vec::new();
+    let nodes = child(root, "peripherals")
+        .into_iter()
+        .flat_map(|node| children(node, "peripheral"));
+    for node in nodes {
+        let peripheral = match node.attribute("derivedFrom") {
+            Some(base) => {
+                let base = peripherals
+                    .iter()
+                    .find(|peripheral| peripheral.name == base)
+                    .ok_or_else(|| {
+                        Error::Invalid(format!("derived peripheral {} is not defined before", base))
+                    })?;
+                Peripheral {
+                    name: text(node, "name")?.to_string(),
+                    description: optional_text(node, "description")
+                        .map(normalize)
+                        .or_else(|| base.description.clone()),
+                    base_address: Some(number(node, "baseAddress")?),
+                    registers: base.registers.clone(),
+                }
+            }
+            None => peripheral(node, properties)?,
+        };
+        peripherals.push(peripheral);
+    }
+
+    Ok(Device {
+        name: text(root, "name")?.to_string(),
+        address_type: String::from("u32"),
+        peripherals,
+    })
+}
+
+/// Parses an SVD file and writes the generated registers in the `OUT_DIR`.
+/// Meant to be called from a `build.rs`, the generated file can then be included using `include!`.
+pub fn build(svd: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<(), Error> {
+    let content = fs::read_to_string(svd.as_ref())?;
+    let device = parse(&content)?;
+    let code = crate::generate(&device)?;
+    crate::write_out_dir(svd, output, &code)?;
+    Ok(())
+}
+
+fn peripheral(node: Node, properties: Properties) -> Result<Peripheral, Error> {
+    let properties = self::properties(node, properties)?;
+    let mut registers = Vec::new();
+    let nodes = child(node, "registers")
+        .into_iter()
+        .flat_map(|node| children(node, "register"));
+    for node in nodes {
+        registers.extend(register(node, properties)?);
+    }
+    registers.sort_by_key(|register| register.offset);
+
+    Ok(Peripheral {
+        name: text(node, "name")?.to_string(),
+        description: optional_text(node, "description").map(normalize),
+        base_address: Some(number(node, "baseAddress")?),
+        registers,
+    })
+}
+
+fn register(node: Node, properties: Properties) -> Result<Vec<RegisterDescription>, Error> {
+    let properties = self::properties(node, properties)?;
+    let name = text(node, "name")?;
+    let description = optional_text(node, "description").map(normalize);
+    let offset = number(node, "addressOffset")?;
+    let read_side_effects = optional_text(node, "readAction").is_some();
+
+    let mut fields = Vec::new();
+    let nodes = child(node, "fields")
+        .into_iter()
+        .flat_map(|node| children(node, "field"));
+    for node in nodes {
+        fields.push(field(node, properties.access)?);
+    }
+    fields.sort_by_key(|field| field.offset);
+
+    let register = RegisterDescription {
+        name: name.to_string(),
+        description,
+        offset,
+        size: properties.size,
+        access: properties.access,
+        reset: properties.reset,
+        read_side_effects,
+        fields,
+    };
+
+    // Arrays of registers are expanded, the `%s` in the name is replaced by the index
+    let dim = match optional_text(node, "dim") {
+        Some(dim) => parse_number(dim)?,
+        None => return Ok(vec![register]),
+    };
+    let increment = number(node, "dimIncrement")?;
+    let indices = match optional_text(node, "dimIndex") {
+        Some(indices) => dim_indices(indices)?,
+        None => (0..dim).map(|index| index.to_string()).collect(),
+    };
+    Ok(indices
+        .iter()
+        .enumerate()
+        .map(|(i, index)| RegisterDescription {
+            name: name.replace("[%s]", index).replace("%s", index),
+            offset: offset + i as u64 * increment,
+            ..register.clone()
+        })
+        .collect())
+}
+
+/// Returns the width of the field spanning from `lsb` to `msb` included
+fn bit_width(msb: u32, lsb: u32) -> Result<u32, Error> {
+    msb.checked_sub(lsb).map(|width| width + 1).ok_or_else(|| {
+        Error::Parse(format!(
+            "invalid bit range, msb {} is below lsb {}",
+            msb, lsb
+        ))
+    })
+}
+
+fn field(node: Node, access: Access) -> Result<FieldDescription, Error> {
+    let (offset, width) = if let Some(range) = optional_text(node, "bitRange") {
+        let (msb, lsb) = range
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split_once(':')
+            .ok_or_else(|| Error::Parse(format!("invalid bit range {}", range)))?;
+        let (msb, lsb) = (parse_number(msb)? as u32, parse_number(lsb)? as u32);
+        (lsb, bit_width(msb, lsb)?)
+    } else if let Some(lsb) = optional_text(node, "lsb") {
+        let lsb = parse_number(lsb)? as u32;
+        let msb = number(node, "msb")? as u32;
+        (lsb, bit_width(msb, lsb)?)
+    } else {
+        (
+            number(node, "bitOffset")? as u32,
+            number(node, "bitWidth")? as u32,
+        )
+    };
+
+    let access = match optional_text(node, "access") {
+        Some(access) => parse_access(access)?,
+        None => access,
+    };
+    Ok(FieldDescription {
+        name: text(node, "name")?.to_string(),
+        description: optional_text(node, "description").map(normalize),
+        offset,
+        width,
+        access,
+    })
+}
+
+fn properties(node: Node, inherited: Properties) -> Result<Properties, Error> {
+    Ok(Properties {
+        size: match optional_text(node, "size") {
+            Some(size) => parse_number(size)? as u32,
+            None => inherited.size,
+        },
+        access: match optional_text(node, "access") {
+            Some(access) => parse_access(access)?,
+            None => inherited.access,
+        },
+        reset: match optional_text(node, "resetValue") {
+            Some(reset) => Some(parse_number(reset)?),
+            None => inherited.reset,
+        },
+    })
+}
+
+fn parse_access(access: &str) -> Result<Access, Error> {
+    match access {
+        "read-only" => Ok(Access::ReadOnly),
+        "write-only" | "writeOnce" => Ok(Access::WriteOnly),
+        "read-write" | "read-writeOnce" => Ok(Access::ReadWrite),
+        access => Err(Error::Parse(format!("unknown access {}", access))),
+    }
+}
+
+fn dim_indices(indices: &str) -> Result<Vec<String>, Error> {
+    match indices.split_once('-') {
+        Some((start, end)) => {
+            let (start, end) = (parse_number(start)?, parse_number(end)?);
+            Ok((start..=end).map(|index| index.to_string()).collect())
+        }
+        None => Ok(indices.split(',').map(|i| i.trim().to_string()).collect()),
+    }
+}
+
+/// Parses a scaled non negative integer of SVD, decimal, hexadecimal or binary
+fn parse_number(value: &str) -> Result<u64, Error> {
+    let value = value.trim();
+    let parsed = if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16)
+    } else if let Some(bin) = value.strip_prefix('#') {
+        u64::from_str_radix(&bin.replace(['x', 'X'], "0"), 2)
+    } else {
+        value.parse()
+    };
+    parsed.map_err(|_| Error::Parse(format!("invalid number {}", value)))
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+fn optional_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name).and_then(|child| child.text())
+}
+
+fn text<'a>(node: Node<'a, '_>, name: &str) -> Result<&'a str, Error> {
+    optional_text(node, name).ok_or_else(|| {
+        Error::Parse(format!(
+            "missing <{}> in <{}>",
+            name,
+            node.tag_name().name()
+        ))
+    })
+}
+
+fn number(node: Node, name: &str) -> Result<u64, Error> {
+    parse_number(text(node, name)?)
+}
@@ -0,0 +1,120 @@
+// Registers of TMP117, generated by device-register-codegen. Do not edit.
+
+/// High-accuracy digital temperature sensor
+pub mod tmp117 {
+    /// Temperature result
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RORegister)]
+    #[register(addr = "0x0", ty = "u8", raw = "u16")]
+    #[register(reset = "0x8000")]
+    pub struct TempResult(pub u16);
+
+    impl TempResult {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u16 = 0x8000;
+    }
+
+    impl Default for TempResult {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u16> for TempResult {
+        fn from(value: u16) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<TempResult> for u16 {
+        fn from(value: TempResult) -> Self {
+            value.0
+        }
+    }
+
+    /// Configuration register
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RWRegister)]
+    #[register(addr = "0x1", ty = "u8", raw = "u16")]
+    #[register(reset = "0x220")]
+    #[register(fields(
+        SOFT_RESET(offset = 1, width = 1, access = "wo"),
+        AVG(offset = 5, width = 2, doc = "Conversion averaging modes"),
+        MOD(offset = 10, width = 2),
+        DATA_READY(offset = 13, width = 1, access = "ro"),
+    ))]
+    pub struct Configuration(pub u16);
+
+    impl Configuration {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u16 = 0x220;
+
+        /// SOFT_RESET
+        pub fn set_soft_reset(&mut self, value: bool) {
+            self.0 = (self.0 & !(0x1 << 1)) | (u16::from(value) << 1);
+        }
+
+        /// Conversion averaging modes
+        pub const fn avg(&self) -> u16 {
+            (self.0 >> 5) & 0x3
+        }
+
+        /// Conversion averaging modes
+        pub fn set_avg(&mut self, value: u16) {
+            self.0 = (self.0 & !(0x3 << 5)) | ((value & 0x3) << 5);
+        }
+
+        /// MOD
+        pub const fn r#mod(&self) -> u16 {
+            (self.0 >> 10) & 0x3
+        }
+
+        /// MOD
+        pub fn set_mod(&mut self, value: u16) {
+            self.0 = (self.0 & !(0x3 << 10)) | ((value & 0x3) << 10);
+        }
+
+        /// DATA_READY
+        pub const fn data_ready(&self) -> bool {
+            (self.0 >> 13) & 0x1 != 0
+        }
+    }
+
+    impl Default for Configuration {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u16> for Configuration {
+        fn from(value: u16) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Configuration> for u16 {
+        fn from(value: Configuration) -> Self {
+            value.0
+        }
+    }
+
+    /// DEVICE_ID
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RORegister)]
+    #[register(addr = "0xf", ty = "u8", raw = "u16")]
+    pub struct DeviceId(pub u16);
+
+    impl From<u16> for DeviceId {
+        fn from(value: u16) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<DeviceId> for u16 {
+        fn from(value: DeviceId) -> Self {
+            value.0
+        }
+    }
+
+    /// All the registers of the peripheral
+    #[derive(device_register::RegisterMap)]
+    #[register_map(ty = "u8", raw = "u16", registers(TempResult, Configuration, DeviceId))]
+    pub struct Map;
+}
@@ -0,0 +1,319 @@
+// Registers of MCU, generated by device-register-codegen. Do not edit.
+
+/// Universal asynchronous receiver transmitter
+pub mod uart0 {
+    /// Base address of the peripheral
+    pub const BASE_ADDRESS: u32 = 0x40000000;
+
+    /// Control register
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RWRegister)]
+    #[register(addr = "0x40000000", ty = "u32", raw = "u32")]
+    #[register(reset = "0x4")]
+    #[register(fields(
+        EN(offset = 0, width = 1, doc = "Enable the peripheral"),
+        MODE(offset = 1, width = 3),
+    ))]
+    pub struct Ctrl(pub u32);
+
+    impl Ctrl {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u32 = 0x4;
+
+        /// Enable the peripheral
+        pub const fn en(&self) -> bool {
+            self.0 & 0x1 != 0
+        }
+
+        /// Enable the peripheral
+        pub fn set_en(&mut self, value: bool) {
+            self.0 = (self.0 & !0x1) | u32::from(value);
+        }
+
+        /// MODE
+        pub const fn mode(&self) -> u32 {
+            (self.0 >> 1) & 0x7
+        }
+
+        /// MODE
+        pub fn set_mode(&mut self, value: u32) {
+            self.0 = (self.0 & !(0x7 << 1)) | ((value & 0x7) << 1);
+        }
+    }
+
+    impl Default for Ctrl {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u32> for Ctrl {
+        fn from(value: u32) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Ctrl> for u32 {
+        fn from(value: Ctrl) -> Self {
+            value.0
+        }
+    }
+
+    /// Status register, cleared on read
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RORegister)]
+    #[register(addr = "0x40000004", ty = "u32", raw = "u32", read_side_effects)]
+    #[register(reset = "0x0")]
+    #[register(fields(
+        RX_READY(offset = 0, width = 1),
+    ))]
+    pub struct Status(pub u32);
+
+    impl Status {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u32 = 0x0;
+
+        /// RX_READY
+        pub const fn rx_ready(&self) -> bool {
+            self.0 & 0x1 != 0
+        }
+    }
+
+    impl Default for Status {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u32> for Status {
+        fn from(value: u32) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Status> for u32 {
+        fn from(value: Status) -> Self {
+            value.0
+        }
+    }
+
+    /// Data register
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RWRegister)]
+    #[register(addr = "0x40000008", ty = "u32", raw = "u32")]
+    #[register(reset = "0x0")]
+    pub struct Data0(pub u32);
+
+    impl Data0 {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u32 = 0x0;
+    }
+
+    impl Default for Data0 {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u32> for Data0 {
+        fn from(value: u32) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Data0> for u32 {
+        fn from(value: Data0) -> Self {
+            value.0
+        }
+    }
+
+    /// Data register
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RWRegister)]
+    #[register(addr = "0x4000000c", ty = "u32", raw = "u32")]
+    #[register(reset = "0x0")]
+    pub struct Data1(pub u32);
+
+    impl Data1 {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u32 = 0x0;
+    }
+
+    impl Default for Data1 {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u32> for Data1 {
+        fn from(value: u32) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Data1> for u32 {
+        fn from(value: Data1) -> Self {
+            value.0
+        }
+    }
+
+    /// All the registers of the peripheral
+    #[derive(device_register::RegisterMap)]
+    #[register_map(ty = "u32", raw = "u32", registers(Ctrl, Status, Data0, Data1))]
+    pub struct Map;
+}
+
+/// Universal asynchronous receiver transmitter
+pub mod uart1 {
+    /// Base address of the peripheral
+    pub const BASE_ADDRESS: u32 = 0x40001000;
+
+    /// Control register
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RWRegister)]
+    #[register(addr = "0x40001000", ty = "u32", raw = "u32")]
+    #[register(reset = "0x4")]
+    #[register(fields(
+        EN(offset = 0, width = 1, doc = "Enable the peripheral"),
+        MODE(offset = 1, width = 3),
+    ))]
+    pub struct Ctrl(pub u32);
+
+    impl Ctrl {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u32 = 0x4;
+
+        /// Enable the peripheral
+        pub const fn en(&self) -> bool {
+            self.0 & 0x1 != 0
+        }
+
+        /// Enable the peripheral
+        pub fn set_en(&mut self, value: bool) {
+            self.0 = (self.0 & !0x1) | u32::from(value);
+        }
+
+        /// MODE
+        pub const fn mode(&self) -> u32 {
+            (self.0 >> 1) & 0x7
+        }
+
+        /// MODE
+        pub fn set_mode(&mut self, value: u32) {
+            self.0 = (self.0 & !(0x7 << 1)) | ((value & 0x7) << 1);
+        }
+    }
+
+    impl Default for Ctrl {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u32> for Ctrl {
+        fn from(value: u32) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Ctrl> for u32 {
+        fn from(value: Ctrl) -> Self {
+            value.0
+        }
+    }
+
+    /// Status register, cleared on read
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RORegister)]
+    #[register(addr = "0x40001004", ty = "u32", raw = "u32", read_side_effects)]
+    #[register(reset = "0x0")]
+    #[register(fields(
+        RX_READY(offset = 0, width = 1),
+    ))]
+    pub struct Status(pub u32);
+
+    impl Status {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u32 = 0x0;
+
+        /// RX_READY
+        pub const fn rx_ready(&self) -> bool {
+            self.0 & 0x1 != 0
+        }
+    }
+
+    impl Default for Status {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u32> for Status {
+        fn from(value: u32) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Status> for u32 {
+        fn from(value: Status) -> Self {
+            value.0
+        }
+    }
+
+    /// Data register
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RWRegister)]
+    #[register(addr = "0x40001008", ty = "u32", raw = "u32")]
+    #[register(reset = "0x0")]
+    pub struct Data0(pub u32);
+
+    impl Data0 {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u32 = 0x0;
+    }
+
+    impl Default for Data0 {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u32> for Data0 {
+        fn from(value: u32) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Data0> for u32 {
+        fn from(value: Data0) -> Self {
+            value.0
+        }
+    }
+
+    /// Data register
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, device_register::RWRegister)]
+    #[register(addr = "0x4000100c", ty = "u32", raw = "u32")]
+    #[register(reset = "0x0")]
+    pub struct Data1(pub u32);
+
+    impl Data1 {
+        /// Value of the register after a reset
+        pub const RESET_VALUE: u32 = 0x0;
+    }
+
+    impl Default for Data1 {
+        fn default() -> Self {
+            Self(Self::RESET_VALUE)
+        }
+    }
+
+    impl From<u32> for Data1 {
+        fn from(value: u32) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Data1> for u32 {
+        fn from(value: Data1) -> Self {
+            value.0
+        }
+    }
+
+    /// All the registers of the peripheral
+    #[derive(device_register::RegisterMap)]
+    #[register_map(ty = "u32", raw = "u32", registers(Ctrl, Status, Data0, Data1))]
+    pub struct Map;
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use device_register::*;
+use device_register_codegen::{map, Access};
+
+#[path = "expected/map.rs"]
+#[allow(dead_code)]
+#[rustfmt::skip]
+mod generated;
+
+use generated::tmp117::*;
+
+const YAML: &str = r#"
+name: TMP117
+description: High-accuracy digital temperature sensor
+size: 16
+registers:
+  - name: TEMP_RESULT
+    description: Temperature result
+    address: 0x00
+    access: ro
+    reset: 0x8000
+  - name: CONFIGURATION
+    description: Configuration register
+    address: 0x01
+    reset: 0x0220
+    fields:
+      - name: SOFT_RESET
+        offset: 1
+        access: wo
+      - name: AVG
+        description: Conversion averaging modes
+        offset: 5
+        width: 2
+      - name: MOD
+        offset: 10
+        width: 2
+      - name: DATA_READY
+        offset: 13
+        access: ro
+  - name: DEVICE_ID
+    address: 0x0F
+    access: read-only
+"#;
+
+const TOML: &str = r#"
+name = "TMP117"
+description = "High-accuracy digital temperature sensor"
+size = 16
+
+[[registers]]
+name = "TEMP_RESULT"
+description = "Temperature result"
+address = 0x00
+access = "ro"
+reset = 0x8000
+
+[[registers]]
+name = "CONFIGURATION"
+description = "Configuration register"
+address = 0x01
+reset = 0x0220
+fields = [
+    { name = "SOFT_RESET", offset = 1, access = "wo" },
+    { name = "AVG", description = "Conversion averaging modes", offset = 5, width = 2 },
+    { name = "MOD", offset = 10, width = 2 },
+    { name = "DATA_READY", offset = 13, access = "ro" },
+]
+
+[[registers]]
+name = "DEVICE_ID"
+address = 0x0F
+access = "read-only"
+"#;
+
+// Mock of the I2C sensor
+#[derive(Default)]
+struct Sensor {
+    registers: HashMap<u8, u16>,
+}
+
+impl<R> RegisterInterface<R, u8> for Sensor
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        Ok(self.registers.get(&R::ADDRESS).copied().unwrap_or(0).into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.registers.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+}
+
+#[test]
+fn parse_map() {
+    let device = map::parse_yaml(YAML).unwrap();
+    assert_eq!(device, map::parse_toml(TOML).unwrap());
+
+    assert_eq!(device.address_type, "u8");
+    let peripheral = &device.peripherals[0];
+    assert_eq!(peripheral.base_address, None);
+    let config = &peripheral.registers[1];
+    assert_eq!(config.size, 16);
+    assert_eq!(config.access, Access::ReadWrite);
+    assert_eq!(config.fields[0].access, Access::WriteOnly);
+    assert_eq!((config.fields[1].offset, config.fields[1].width), (5, 2));
+    assert_eq!(peripheral.registers[2].access, Access::ReadOnly);
+
+    assert!(map::parse_yaml("name: TMP117\nregisters: []\nunknown: 1").is_err());
+}
+
+#[test]
+fn generate_map() {
+    let device = map::parse_yaml(YAML).unwrap();
+    let code = device_register_codegen::generate(&device).unwrap();
+    assert_eq!(code, include_str!("expected/map.rs"));
+}
+
+#[test]
+fn use_generated_map() {
+    let mut sensor = Sensor::default();
+    sensor.registers.insert(TempResult::ADDRESS, 0x0C80);
+
+    let temp: TempResult = sensor.read().unwrap();
+    assert_eq!(temp.0, 0x0C80);
+    assert_eq!(TempResult::default().0, 0x8000);
+
+    let mut config = Configuration::default();
+    config.set_mod(0b11);
+    config.set_soft_reset(true);
+    sensor.write(config).unwrap();
+    sensor
+        .edit(|r: &mut Configuration| {
+            assert_eq!(r.r#mod(), 0b11);
+            r.set_avg(0b01);
+        })
+        .unwrap();
+    assert_eq!(sensor.registers[&0x01], 0x0E22);
+
+    let snapshot = dump::<Map, _>(&mut sensor).unwrap();
+    assert_eq!(snapshot.get("TempResult"), Some(&0x0C80));
+    assert_eq!(snapshot.get("DeviceId"), Some(&0));
+}
+
+#[test]
+fn export_generated_map() {
+    // The exported JSON is a valid register map, JSON being a subset of YAML
+    let mut json = String::new();
+    export::json::<Map, _>(&mut json).unwrap();
+    let exported = map::parse_yaml(&json).unwrap();
+    let original = map::parse_yaml(YAML).unwrap();
+
+    let exported = &exported.peripherals[0].registers;
+    let original = &original.peripherals[0].registers;
+    assert_eq!(exported.len(), original.len());
+    for (exported, original) in exported.iter().zip(original) {
+        assert_eq!(exported.offset, original.offset);
+        assert_eq!(exported.size, original.size);
+        assert_eq!(exported.access, original.access);
+    }
+}
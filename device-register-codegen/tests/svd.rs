@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use device_register::*;
+use device_register_codegen::{svd, Access};
+
+#[path = "expected/svd.rs"]
+#[allow(dead_code)]
+#[rustfmt::skip]
+mod generated;
+
+use generated::{uart0, uart1};
+
+const SVD: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<device schemaVersion="1.3">
+  <name>MCU</name>
+  <size>32</size>
+  <access>read-write</access>
+  <resetValue>0x00000000</resetValue>
+  <peripherals>
+    <peripheral>
+      <name>UART0</name>
+      <description>Universal asynchronous
+        receiver transmitter</description>
+      <baseAddress>0x40000000</baseAddress>
+      <registers>
+        <register>
+          <name>CTRL</name>
+          <description>Control register</description>
+          <addressOffset>0x0</addressOffset>
+          <resetValue>0x00000004</resetValue>
+          <fields>
+            <field>
+              <name>EN</name>
+              <description>Enable the peripheral</description>
+              <bitOffset>0</bitOffset>
+              <bitWidth>1</bitWidth>
+            </field>
+            <field>
+              <name>MODE</name>
+              <bitRange>[3:1]</bitRange>
+            </field>
+          </fields>
+        </register>
+        <register>
+          <name>STATUS</name>
+          <description>Status register, cleared on read</description>
+          <addressOffset>0x4</addressOffset>
+          <access>read-only</access>
+          <readAction>clear</readAction>
+          <fields>
+            <field>
+              <name>RX_READY</name>
+              <lsb>0</lsb>
+              <msb>0</msb>
+            </field>
+          </fields>
+        </register>
+        <register>
+          <dim>2</dim>
+          <dimIncrement>0x4</dimIncrement>
+          <name>DATA[%s]</name>
+          <description>Data register</description>
+          <addressOffset>0x8</addressOffset>
+        </register>
+      </registers>
+    </peripheral>
+    <peripheral derivedFrom="UART0">
+      <name>UART1</name>
+      <baseAddress>0x40001000</baseAddress>
+    </peripheral>
+  </peripherals>
+</device>
+"#;
+
+// Mock of a memory mapped device
+#[derive(Default)]
+struct Memory {
+    words: HashMap<u32, u32>,
+}
+
+impl<R> RegisterInterface<R, u32> for Memory
+where
+    R: Register<Address = u32> + Clone + From<u32>,
+    u32: From<R>,
+{
+    type Error = ();
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        Ok(self.words.get(&R::ADDRESS).copied().unwrap_or(0).into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.words.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+}
+
+#[test]
+fn parse_svd() {
+    let device = svd::parse(SVD).unwrap();
+    assert_eq!(device.name, "MCU");
+    assert_eq!(device.peripherals.len(), 2);
+
+    let uart0 = &device.peripherals[0];
+    assert_eq!(
+        uart0.description.as_deref(),
+        Some("Universal asynchronous receiver transmitter")
+    );
+    let names: Vec<_> = uart0.registers.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["CTRL", "STATUS", "DATA0", "DATA1"]);
+    assert_eq!(uart0.registers[0].reset, Some(0x4));
+    assert_eq!(uart0.registers[1].access, Access::ReadOnly);
+    assert!(uart0.registers[1].read_side_effects);
+    assert_eq!(uart0.registers[3].offset, 0xC);
+
+    let mode = &uart0.registers[0].fields[1];
+    assert_eq!((mode.offset, mode.width), (1, 3));
+
+    let uart1 = &device.peripherals[1];
+    assert_eq!(uart1.base_address, Some(0x4000_1000));
+    assert_eq!(uart1.registers, uart0.registers);
+}
+
+#[test]
+fn generate_svd() {
+    let device = svd::parse(SVD).unwrap();
+    let code = device_register_codegen::generate(&device).unwrap();
+    assert_eq!(code, include_str!("expected/svd.rs"));
+}
+
+#[test]
+fn use_generated_svd() {
+    let mut memory = Memory::default();
+
+    let mut ctrl = uart1::Ctrl::default();
+    assert_eq!(ctrl.mode(), 2);
+    ctrl.set_en(true);
+    ctrl.set_mode(5);
+    memory.write(ctrl).unwrap();
+    assert_eq!(memory.words[&0x4000_1000], 0b1011);
+
+    let ctrl: uart1::Ctrl = memory.read().unwrap();
+    assert!(ctrl.en());
+    assert_eq!(ctrl.mode(), 5);
+
+    assert_eq!(uart0::Data1::ADDRESS, 0x4000_000C);
+    assert_eq!(uart0::Status::PERMISSIONS, Permissions::READ);
+    assert!(uart0::Map::REGISTERS[1].read_side_effects);
+
+    let snapshot = dump::<uart1::Map, _>(&mut memory).unwrap();
+    assert_eq!(snapshot.get("Ctrl"), Some(&0b1011));
+    assert_eq!(snapshot.get("Status"), None);
+}
+
+#[test]
+fn export_generated_svd() {
+    let mut exported = String::new();
+    export::svd::<uart0::Map, _>(&mut exported).unwrap();
+    let exported = svd::parse(&exported).unwrap();
+    let original = svd::parse(SVD).unwrap();
+
+    let exported = &exported.peripherals[0].registers;
+    let original = &original.peripherals[0];
+    assert_eq!(exported.len(), original.registers.len());
+    for (exported, register) in exported.iter().zip(&original.registers) {
+        assert_eq!(
+            exported.offset,
+            original.base_address.unwrap() + register.offset
+        );
+        assert_eq!(exported.access, register.access);
+        assert_eq!(exported.read_side_effects, register.read_side_effects);
+    }
+}
+
+#[test]
+fn reversed_bit_range() {
+    for field in ["<bitRange>[0:3]</bitRange>", "<lsb>3</lsb><msb>0</msb>"] {
+        let svd = format!(
+            r#"<device><name>MCU</name><size>32</size><peripherals><peripheral>
+            <name>UART</name><baseAddress>0</baseAddress><registers><register>
+            <name>CR</name><addressOffset>0</addressOffset>
+            <fields><field><name>EN</name>{}</field></fields>
+            </register></registers></peripheral></peripherals></device>"#,
+            field
+        );
+        let err = svd::parse(&svd).unwrap_err();
+        assert!(
+            matches!(err, device_register_codegen::Error::Parse(_)),
+            "{}",
+            err
+        );
+    }
+}
@@ -4,7 +4,7 @@
 use darling::{FromDeriveInput, FromMeta, ToTokens};
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::DeriveInput;
+use syn::{spanned::Spanned, DeriveInput};
 
 /// The valid values of an address
 enum Address {
@@ -50,6 +50,183 @@ impl FromMeta for Address {
     }
 }
 
+/// An unsigned integer, either as an integer literal or a string, e.g. `reset = "0x0220"`
+struct Integer(u64);
+
+impl FromMeta for Integer {
+    fn from_value(value: &syn::Lit) -> darling::Result<Self> {
+        let value = match value {
+            syn::Lit::Int(int) => int.base10_parse(),
+            syn::Lit::Str(str) => str.parse::<syn::LitInt>()?.base10_parse(),
+            _ => return Err(darling::Error::unexpected_lit_type(value)),
+        };
+        Ok(Integer(value?))
+    }
+}
+
+/// The access of a field, using the short names of the derives, e.g. `access = "ro"`
+struct Access(Vec<Permission>);
+
+impl FromMeta for Access {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        let permissions = match value {
+            "ro" => vec![Permission::Read],
+            "wo" => vec![Permission::Write],
+            "eo" => vec![Permission::Edit],
+            "re" => vec![Permission::Read, Permission::Edit],
+            "rw" => vec![Permission::Read, Permission::Write, Permission::Edit],
+            _ => return Err(darling::Error::unknown_value(value)),
+        };
+        Ok(Access(permissions))
+    }
+}
+
+/// The arguments of a field of the register, e.g. `MODE(offset = 4, width = 2)`
+#[derive(darling::FromMeta)]
+struct Field {
+    /// The offset of the least significant bit of the field
+    offset: u32,
+
+    /// The number of bits of the field, defaults to 1
+    #[darling(default = "Field::default_width")]
+    width: u32,
+
+    /// The access of the field, defaults to the permissions of the register
+    access: Option<Access>,
+
+    /// The description of the field
+    doc: Option<String>,
+
+    /// The enumeration of the values of the field, generates the accessors of the field
+    #[darling(rename = "enum")]
+    ty: Option<syn::Path>,
+}
+
+impl Field {
+    fn default_width() -> u32 {
+        1
+    }
+
+    /// Returns the mask of the field, not shifted at its offset
+    fn mask(&self) -> u64 {
+        if self.width >= 64 {
+            u64::MAX
+        } else {
+            (1 << self.width) - 1
+        }
+    }
+}
+
+/// The fields of the register, named by their path, e.g. `fields(EN(offset = 0), MODE(offset = 4, width = 2))`,
+/// with the span of their name to report the invalid fields
+struct Fields(Vec<(String, Field, proc_macro2::Span)>);
+
+impl FromMeta for Fields {
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
+        let mut errors = darling::Error::accumulator();
+        let mut fields = Vec::new();
+        for item in items {
+            let field = match item {
+                syn::NestedMeta::Meta(meta @ syn::Meta::List(list)) => {
+                    let name = list.path.to_token_stream().to_string();
+                    let name = name.trim_start_matches("r#").to_string();
+                    Field::from_meta(meta).map(|field| (name, field, list.path.span()))
+                }
+                item => Err(darling::Error::unsupported_format("field").with_span(item)),
+            };
+            if let Some(field) = errors.handle(field) {
+                fields.push(field);
+            }
+        }
+        errors.finish_with(Fields(fields))
+    }
+}
+
+impl Fields {
+    /// Rejects the empty fields, the fields beyond the bits of the register and the overlapping fields
+    fn validate(&self, raw: Option<&syn::Type>) -> darling::Result<()> {
+        let bits = raw.and_then(raw_bits).unwrap_or(64);
+        let mut errors = darling::Error::accumulator();
+        let mut fields: Vec<_> = self.0.iter().collect();
+        fields.sort_by_key(|(_, field, _)| field.offset);
+        let mut end: Option<(&String, u32)> = None;
+        for (name, field, span) in fields {
+            let error = if field.width == 0 {
+                Some(format!(
+                    "the field `{}` must have a width of at least 1",
+                    name
+                ))
+            } else if field
+                .offset
+                .checked_add(field.width)
+                .filter(|&field_end| field_end <= bits)
+                .is_none()
+            {
+                Some(format!(
+                    "the field `{}` exceeds the {} bits of the register",
+                    name, bits
+                ))
+            } else if let Some((other, _)) = end.filter(|&(_, end)| field.offset < end) {
+                Some(format!(
+                    "the field `{}` overlaps the field `{}`",
+                    name, other
+                ))
+            } else {
+                None
+            };
+            match error {
+                Some(error) => errors.push(darling::Error::custom(error).with_span(span)),
+                // Keeps the field reaching the furthest, a shorter field can follow a longer one
+                None if end.is_some_and(|(_, end)| field.offset + field.width <= end) => {}
+                None => end = Some((name, field.offset + field.width)),
+            }
+        }
+        errors.finish()
+    }
+}
+
+/// The number of bits of a raw representation, `None` for the types other than the unsigned integers
+fn raw_bits(raw: &syn::Type) -> Option<u32> {
+    match raw {
+        syn::Type::Path(path) if path.qself.is_none() => {
+            match path.path.get_ident()?.to_string().as_str() {
+                "u8" => Some(8),
+                "u16" => Some(16),
+                "u32" => Some(32),
+                "u64" => Some(64),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The value of a key, an integer converted to the unlock register or an expression as a string
+enum KeyValue {
+    Int(syn::LitInt),
+    Expr(Box<syn::Expr>),
+}
+
+impl FromMeta for KeyValue {
+    fn from_value(value: &syn::Lit) -> darling::Result<Self> {
+        match value {
+            syn::Lit::Int(int) => Ok(KeyValue::Int(int.clone())),
+            syn::Lit::Str(str) => Ok(KeyValue::Expr(Box::new(str.parse()?))),
+            _ => Err(darling::Error::unexpected_lit_type(value)),
+        }
+    }
+}
+
+/// The arguments of a protected register, e.g. `protected(by = "Unlock", key = 0xA5)`
+#[derive(darling::FromMeta)]
+struct Protected {
+    /// The register the key is written to
+    by: syn::Path,
+
+    /// The key written to unlock the register
+    key: KeyValue,
+}
+
 /// The arguments passed to the register helper attribute
 #[derive(darling::FromDeriveInput)]
 #[darling(attributes(register))]
@@ -59,23 +236,383 @@ struct Register {
 
     /// The type of the address, defaults to a u8
     ty: Option<syn::Type>,
+
+    /// If reading the register modifies the state of the device
+    read_side_effects: darling::util::Flag,
+
+    /// The raw representation of the register, used for serialization and by the interfaces transferring bytes
+    raw: Option<syn::Type>,
+
+    /// The value of the register after a reset
+    reset: Option<Integer>,
+
+    /// The fields of the register
+    fields: Option<Fields>,
+
+    /// The page of the register, for devices with paged registers
+    page: Option<Integer>,
+
+    /// The unlock sequence written before each write of the register
+    protected: Option<Protected>,
+
+    /// If the register implements `TryFromRaw` itself instead of the derive
+    try_from_raw: darling::util::Flag,
+
+    /// If the register is a FIFO, read in bulk with `ReadFifo` instead of `ReadRegister`
+    fifo: darling::util::Flag,
+
+    /// If the register is serialized as its raw representation, requires `raw` and the `serde` feature
+    serde: darling::util::Flag,
+}
+
+/// Joins the doc comments of the item, used as the description of the register
+fn description(attrs: &[syn::Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(doc),
+                ..
+            })) => Some(doc.value().trim().to_string()),
+            _ => None,
+        })
+        .take_while(|line| line != LAYOUT_HEADING)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
 }
 
-fn impl_register(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+/// The permissions a derive grants to the register
+#[derive(PartialEq)]
+enum Permission {
+    Read,
+    Write,
+    Edit,
+}
+
+impl ToTokens for Permission {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let flag = match self {
+            Permission::Read => quote!(READ),
+            Permission::Write => quote!(WRITE),
+            Permission::Edit => quote!(EDIT),
+        };
+        tokens.extend(quote!(device_register::Permissions::#flag));
+    }
+}
+
+fn impl_register(
+    ast: &syn::DeriveInput,
+    permissions: &[Permission],
+) -> syn::Result<proc_macro2::TokenStream> {
     let name = &ast.ident;
     let reg = Register::from_derive_input(ast)?;
     let addr = reg.addr;
     let ty = reg.ty.unwrap_or_else(|| syn::parse_str("u8").unwrap());
+    let fifo = reg.fifo.is_present();
+    if fifo && !matches!(permissions, [Permission::Read]) {
+        return Err(syn::Error::new_spanned(
+            name,
+            "a fifo register must be derived with RORegister",
+        ));
+    }
+    // Reading a FIFO pops its values
+    let read_side_effects = reg.read_side_effects.is_present() || fifo;
     let (impl_gen, type_gen, where_gen) = &ast.generics.split_for_impl();
+    let serde = match (reg.serde.is_present(), &reg.raw) {
+        (false, _) => quote!(),
+        (true, Some(raw)) => impl_serde(ast, raw)?,
+        (true, None) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "serde requires the raw representation of the register, e.g. `raw = \"u16\"`",
+            ))
+        }
+    };
+    if let Some(fields) = &reg.fields {
+        fields.validate(reg.raw.as_ref())?;
+    }
+    let accessors = impl_field_accessors(ast, reg.raw.as_ref(), &reg.fields, permissions)?;
+    let try_from_raw = if reg.try_from_raw.is_present() {
+        quote!()
+    } else {
+        impl_try_from_raw(ast, reg.raw.as_ref(), &reg.fields)
+    };
+    let raw = reg.raw.map(|raw| {
+        quote! {
+            impl #impl_gen device_register::RawRegister for #name #type_gen #where_gen {
+                type Raw = #raw;
+            }
+        }
+    });
+    let fifo = fifo.then(|| {
+        quote! {
+            impl #impl_gen device_register::FifoRegister for #name #type_gen #where_gen {}
+        }
+    });
+    let description = description(&ast.attrs);
+    let reset = match reg.reset {
+        Some(Integer(reset)) => quote!(Some(#reset)),
+        None => quote!(None),
+    };
+    let page = match reg.page {
+        Some(Integer(page)) => {
+            let page = u8::try_from(page).map_err(|_| {
+                syn::Error::new_spanned(name, "the page of a register must fit in a u8")
+            })?;
+            quote!(Some(#page))
+        }
+        None => quote!(None),
+    };
+    let (protection, protected) = match reg.protected {
+        Some(Protected { by, key }) => {
+            let key = match key {
+                KeyValue::Int(int) => quote!(<#by as From<_>>::from(#int)),
+                KeyValue::Expr(expr) => quote!(#expr),
+            };
+            let protected = quote! {
+                impl #impl_gen device_register::ProtectedRegister for #name #type_gen #where_gen {
+                    type Unlock = #by;
+
+                    fn key() -> Self::Unlock {
+                        #key
+                    }
+                }
+            };
+            (quote!(device_register::Key<Self>), protected)
+        }
+        None => (quote!(device_register::Unprotected), quote!()),
+    };
+    let mut fields = reg.fields.map(|fields| fields.0).unwrap_or_default();
+    fields.sort_by_key(|(_, field, _)| field.offset);
+    let fields = fields.iter().map(|(name, field, _)| {
+        let Field {
+            offset, width, doc, ..
+        } = field;
+        let doc = doc.as_deref().unwrap_or_default();
+        let field_permissions = field
+            .access
+            .as_ref()
+            .map_or(permissions, |access| &access.0);
+        quote! {
+            device_register::FieldInfo {
+                name: #name,
+                description: #doc,
+                offset: #offset,
+                width: #width,
+                permissions: device_register::Permissions::NONE #(.union(#field_permissions))*,
+            }
+        }
+    });
     Ok(quote! {
+        #serde
+        #raw
+        #fifo
+        #protected
+        #accessors
+        #try_from_raw
+
         #[allow(dead_code)]
         impl #impl_gen device_register::Register for #name #type_gen #where_gen {
             type Address = #ty;
+            type Protection = #protection;
             const ADDRESS: Self::Address = #addr;
+            const PERMISSIONS: device_register::Permissions =
+                device_register::Permissions::NONE #(.union(#permissions))*;
+            const NAME: &'static str = stringify!(#name);
+            const READ_SIDE_EFFECTS: bool = #read_side_effects;
+            const DESCRIPTION: &'static str = #description;
+            const RESET: Option<u64> = #reset;
+            const FIELDS: &'static [device_register::FieldInfo] = &[#(#fields),*];
+            const PAGE: Option<u8> = #page;
+
+            fn decode(register: Self) -> Result<Self, device_register::InvalidValue> {
+                <Self as device_register::TryFromRaw>::try_from_raw(register)
+            }
         }
     })
 }
 
+/// The identifier of the getter of a field, a raw identifier if its name is a keyword, e.g. `r#mod` for `MOD`
+fn accessor_ident(name: &str) -> syn::Ident {
+    let accessor = accessor_name(name);
+    match syn::parse_str::<syn::Ident>(&accessor) {
+        Ok(ident) => ident,
+        // These keywords can't be raw identifiers
+        Err(_) if matches!(accessor.as_str(), "self" | "super" | "crate") => {
+            quote::format_ident!("{}_", accessor)
+        }
+        Err(_) => syn::Ident::new_raw(&accessor, proc_macro2::Span::call_site()),
+    }
+}
+
+/// Converts the name of a field to the name of its accessor, e.g. `DATA_READY` to `data_ready`
+fn accessor_name(name: &str) -> String {
+    let mut accessor = String::new();
+    let mut prev = None;
+    for c in name.chars() {
+        if c.is_uppercase() && prev.is_some_and(char::is_lowercase) {
+            accessor.push('_');
+        }
+        accessor.extend(c.to_lowercase());
+        prev = Some(c);
+    }
+    accessor
+}
+
+/// Getters and setters of the fields referencing an enumeration
+fn impl_field_accessors(
+    ast: &syn::DeriveInput,
+    raw: Option<&syn::Type>,
+    fields: &Option<Fields>,
+    permissions: &[Permission],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let (impl_gen, type_gen, where_gen) = &ast.generics.split_for_impl();
+    let fields: Vec<_> = fields
+        .iter()
+        .flat_map(|fields| &fields.0)
+        .filter_map(|(name, field, _)| field.ty.as_ref().map(|ty| (name, field, ty)))
+        .collect();
+    if fields.is_empty() {
+        return Ok(quote!());
+    }
+    let raw = raw.ok_or_else(|| {
+        syn::Error::new_spanned(name, "fields with an enum require the raw attribute")
+    })?;
+
+    let accessors = fields.iter().map(|(field_name, field, ty)| {
+        let getter = accessor_ident(field_name);
+        let setter = quote::format_ident!("set_{}", accessor_name(field_name));
+        let offset = field.offset;
+        let width = field.width;
+        let mask = field.mask();
+        let writable = match &field.access {
+            Some(access) => access.0.iter().any(|p| !matches!(p, Permission::Read)),
+            None => permissions.iter().any(|p| !matches!(p, Permission::Read)),
+        };
+        let getter_doc = format!("Returns the field `{}`", field_name);
+        let setter_doc = format!("Sets the field `{}`", field_name);
+        let setter = writable.then(|| {
+            quote! {
+                #[doc = #setter_doc]
+                #vis fn #setter(&mut self, value: #ty) {
+                    let raw: #raw = ::core::clone::Clone::clone(self).into();
+                    let mask: u64 = #mask << #offset;
+                    let bits = device_register::FieldEnum::bits(value) << #offset;
+                    let raw = (u64::from(raw) & !mask) | (bits & mask);
+                    *self = Self::from(raw as #raw);
+                }
+            }
+        });
+        quote! {
+            #[doc = #getter_doc]
+            #vis fn #getter(&self) -> <#ty as device_register::FieldEnum>::Decoded {
+                const _: () = assert!(
+                    <#ty as device_register::FieldEnum>::WIDTH == #width,
+                    "the width of the enum does not match the width of the field"
+                );
+                let raw: #raw = ::core::clone::Clone::clone(self).into();
+                let bits = (u64::from(raw) >> #offset) & #mask;
+                <#ty as device_register::FieldEnum>::decode(stringify!(#name), #field_name, bits)
+            }
+
+            #setter
+        }
+    });
+    Ok(quote! {
+        #[allow(dead_code)]
+        impl #impl_gen #name #type_gen #where_gen {
+            #(#accessors)*
+        }
+    })
+}
+
+/// Decoding of the register, rejects the reserved values of the fields referencing an enumeration
+fn impl_try_from_raw(
+    ast: &syn::DeriveInput,
+    raw: Option<&syn::Type>,
+    fields: &Option<Fields>,
+) -> proc_macro2::TokenStream {
+    let name = &ast.ident;
+    let (impl_gen, type_gen, where_gen) = &ast.generics.split_for_impl();
+    let checks: Vec<_> = fields
+        .iter()
+        .flat_map(|fields| &fields.0)
+        .filter_map(|(field_name, field, _)| {
+            let ty = field.ty.as_ref()?;
+            let offset = field.offset;
+            let mask = field.mask();
+            Some(quote! {
+                <#ty as device_register::FieldEnum>::try_decode(
+                    stringify!(#name),
+                    #field_name,
+                    (bits >> #offset) & #mask,
+                )?;
+            })
+        })
+        .collect();
+    // The accessors already report a missing raw attribute
+    let body = match raw {
+        Some(raw) if !checks.is_empty() => quote! {
+            let raw: #raw = ::core::clone::Clone::clone(&register).into();
+            let bits = u64::from(raw);
+            #(#checks)*
+            Ok(register)
+        },
+        _ => quote!(Ok(register)),
+    };
+    quote! {
+        impl #impl_gen device_register::TryFromRaw for #name #type_gen #where_gen {
+            fn try_from_raw(register: Self) -> Result<Self, device_register::InvalidValue> {
+                #body
+            }
+        }
+    }
+}
+
+/// Serialize and deserialize the register as its raw representation
+#[cfg(feature = "serde")]
+fn impl_serde(ast: &syn::DeriveInput, raw: &syn::Type) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let (impl_gen, type_gen, where_gen) = &ast.generics.split_for_impl();
+    let mut de_generics = ast.generics.clone();
+    de_generics.params.insert(0, syn::parse_quote!('de));
+    let (de_impl_gen, _, _) = de_generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_gen device_register::serde::Serialize for #name #type_gen #where_gen {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: device_register::serde::Serializer,
+            {
+                let raw: #raw = ::core::clone::Clone::clone(self).into();
+                device_register::serde::Serialize::serialize(&raw, serializer)
+            }
+        }
+
+        impl #de_impl_gen device_register::serde::Deserialize<'de> for #name #type_gen #where_gen {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: device_register::serde::Deserializer<'de>,
+            {
+                let raw = <#raw as device_register::serde::Deserialize<'de>>::deserialize(deserializer)?;
+                Ok(raw.into())
+            }
+        }
+    })
+}
+
+#[cfg(not(feature = "serde"))]
+fn impl_serde(ast: &syn::DeriveInput, _raw: &syn::Type) -> syn::Result<proc_macro2::TokenStream> {
+    Err(syn::Error::new_spanned(
+        &ast.ident,
+        "serde requires the `serde` feature of device-register",
+    ))
+}
+
 /// Create a read only register
 #[proc_macro_derive(RORegister, attributes(register))]
 pub fn ro_register(input: TokenStream) -> TokenStream {
@@ -85,12 +622,25 @@ pub fn ro_register(input: TokenStream) -> TokenStream {
 
     // Build the impl
 
-    let mut output = impl_register(&ast).unwrap_or_else(syn::Error::into_compile_error);
-    output.extend(impl_ro_register(&ast));
-    output.into()
+    // The other impls are skipped on an error, the register would not implement `Register`
+    impl_register(&ast, &[Permission::Read])
+        .map(|mut output| {
+            output.extend(impl_ro_register(&ast));
+            output
+        })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Returns true if the register is declared as a FIFO, which is not readable with `ReadRegister`
+fn is_fifo(ast: &syn::DeriveInput) -> bool {
+    Register::from_derive_input(ast).is_ok_and(|reg| reg.fifo.is_present())
 }
 
 fn impl_ro_register(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
+    if is_fifo(ast) {
+        return proc_macro2::TokenStream::new();
+    }
     let name = &ast.ident;
     let (impl_gen, type_gen, where_gen) = &ast.generics.split_for_impl();
 
@@ -107,9 +657,13 @@ pub fn eo_register(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
 
     // Build the impl
-    let mut output = impl_register(&ast).unwrap_or_else(syn::Error::into_compile_error);
-    output.extend(impl_eo_register(&ast));
-    output.into()
+    impl_register(&ast, &[Permission::Edit])
+        .map(|mut output| {
+            output.extend(impl_eo_register(&ast));
+            output
+        })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 /// Create a read/edit register
@@ -119,10 +673,14 @@ pub fn re_register(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
 
     // Build the impl
-    let mut output = impl_register(&ast).unwrap_or_else(syn::Error::into_compile_error);
-    output.extend(impl_ro_register(&ast));
-    output.extend(impl_eo_register(&ast));
-    output.into()
+    impl_register(&ast, &[Permission::Read, Permission::Edit])
+        .map(|mut output| {
+            output.extend(impl_ro_register(&ast));
+            output.extend(impl_eo_register(&ast));
+            output
+        })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 fn impl_eo_register(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
@@ -141,9 +699,13 @@ pub fn wo_register(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
 
     // Build the impl
-    let mut output = impl_register(&ast).unwrap_or_else(syn::Error::into_compile_error);
-    output.extend(impl_wo_register(&ast));
-    output.into()
+    impl_register(&ast, &[Permission::Write])
+        .map(|mut output| {
+            output.extend(impl_wo_register(&ast));
+            output
+        })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 /// Create a read/write register
@@ -153,11 +715,18 @@ pub fn rw_register(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
 
     // Build the impl
-    let mut output = impl_register(&ast).unwrap_or_else(syn::Error::into_compile_error);
-    output.extend(impl_ro_register(&ast));
-    output.extend(impl_eo_register(&ast));
-    output.extend(impl_wo_register(&ast));
-    output.into()
+    impl_register(
+        &ast,
+        &[Permission::Read, Permission::Write, Permission::Edit],
+    )
+    .map(|mut output| {
+        output.extend(impl_ro_register(&ast));
+        output.extend(impl_eo_register(&ast));
+        output.extend(impl_wo_register(&ast));
+        output
+    })
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
 }
 
 fn impl_wo_register(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
@@ -168,3 +737,510 @@ fn impl_wo_register(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
         impl #impl_gen device_register::WritableRegister for #name #type_gen #where_gen {}
     }
 }
+
+/// Heading of the layout appended to the documentation by `register_doc`, the description of the register stops there
+const LAYOUT_HEADING: &str = "# Layout";
+
+/// Short name of the access granted by a register derive, e.g. `ro` for `RORegister`
+fn derive_access(attrs: &[syn::Attribute]) -> Option<&'static str> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("derive"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) => {
+                match path.segments.last()?.ident.to_string().as_str() {
+                    "RORegister" => Some("ro"),
+                    "WORegister" => Some("wo"),
+                    "EORegister" => Some("eo"),
+                    "RERegister" => Some("re"),
+                    "RWRegister" => Some("rw"),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+}
+
+/// Short name of the access of a field
+fn field_access(access: &Access) -> &'static str {
+    let [read, write, edit] =
+        [Permission::Read, Permission::Write, Permission::Edit].map(|p| access.0.contains(&p));
+    match (read, write, edit) {
+        (true, false, false) => "ro",
+        (false, true, false) => "wo",
+        (false, false, true) => "eo",
+        (true, false, true) => "re",
+        _ => "rw",
+    }
+}
+
+/// Formats a value as hexadecimal, padded to its number of bits
+fn hex(value: u64, bits: u32) -> String {
+    format!("{:#0width$x}", value, width = bits.div_ceil(4) as usize + 2)
+}
+
+/// The lines of the layout of the register, empty if it has neither a reset value nor fields
+fn layout_doc(reg: &Register, access: &'static str) -> Vec<String> {
+    let fields = reg.fields.as_ref().map_or(&[][..], |fields| &fields.0[..]);
+    if reg.reset.is_none() && fields.is_empty() {
+        return Vec::new();
+    }
+    let bits = reg.raw.as_ref().and_then(raw_bits).unwrap_or(64);
+    let reset = reg.reset.as_ref().map(|Integer(reset)| *reset);
+    let mut lines = vec![String::new(), LAYOUT_HEADING.to_string(), String::new()];
+    lines.push(format!(
+        "Address: `{}`, access: `{}`, reset: `{}`",
+        reg.addr.to_token_stream().to_string().trim_matches('"'),
+        access,
+        reset.map_or("-".to_string(), |reset| hex(reset, bits))
+    ));
+    if fields.is_empty() {
+        return lines;
+    }
+
+    lines.push(String::new());
+    lines.push("| Bits | Field | Access | Reset | Description |".to_string());
+    lines.push("|---|---|---|---|---|".to_string());
+    let mut fields: Vec<_> = fields.iter().collect();
+    fields.sort_by_key(|(_, field, _)| core::cmp::Reverse(field.offset));
+    for (name, field, _) in fields {
+        let bits = if field.width == 1 {
+            field.offset.to_string()
+        } else {
+            format!("{}:{}", field.offset + field.width - 1, field.offset)
+        };
+        let reset = reset.map_or("-".to_string(), |reset| {
+            let value = reset.checked_shr(field.offset).unwrap_or(0) & field.mask();
+            hex(value, field.width)
+        });
+        let description = field
+            .doc
+            .as_deref()
+            .unwrap_or_default()
+            .replace('|', "\\|")
+            .replace('\n', " ");
+        lines.push(format!(
+            "| {} | {} | {} | {} | {} |",
+            bits,
+            name,
+            field.access.as_ref().map_or(access, field_access),
+            reset,
+            description
+        ));
+    }
+    lines
+}
+
+/// Appends the layout of the register to its documentation, with its address, access and reset value and a table of its fields.
+/// Placed before the derive of the register, which it reads the access from. The layout is not part of the `DESCRIPTION`.
+///
+/// ```ignore
+/// /// Configuration of the device
+/// #[register_doc]
+/// #[derive(RWRegister)]
+/// #[register(addr = "0x02", reset = "0x0220", fields(MODE(offset = 10, width = 2)))]
+/// pub struct Config(pub u16);
+/// ```
+#[proc_macro_attribute]
+pub fn register_doc(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut ast = syn::parse_macro_input!(input as DeriveInput);
+    let Some(access) = derive_access(&ast.attrs) else {
+        return syn::Error::new_spanned(
+            &ast.ident,
+            "register_doc must be placed before the derive of the register",
+        )
+        .into_compile_error()
+        .into();
+    };
+    // The errors of the attributes are reported by the derive
+    if let Ok(reg) = Register::from_derive_input(&ast) {
+        let lines = layout_doc(&reg, access);
+        ast.attrs
+            .extend(lines.iter().map(|line| syn::parse_quote!(#[doc = #line])));
+    }
+    ast.into_token_stream().into()
+}
+
+/// The arguments passed to the register_map helper attribute
+#[derive(darling::FromDeriveInput)]
+#[darling(attributes(register_map))]
+struct RegisterMap {
+    /// The type of the address of the registers, defaults to a u8
+    ty: Option<syn::Type>,
+
+    /// The raw representation of the registers
+    raw: syn::Type,
+
+    /// The registers of the map
+    registers: darling::util::PathList,
+
+    /// The registers restored last, in this order
+    restore_order: Option<darling::util::PathList>,
+}
+
+fn impl_register_map(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let map = RegisterMap::from_derive_input(ast)?;
+    let ty = map.ty.unwrap_or_else(|| syn::parse_str("u8").unwrap());
+    let raw = map.raw;
+    let registers = &map.registers[..];
+    let len = registers.len();
+    let indices: Vec<usize> = (0..len).collect();
+    let empty = registers.iter().map(|_| quote!(None));
+    let (first, rest) = registers.split_first().ok_or_else(|| {
+        syn::Error::new_spanned(name, "a register map requires at least one register")
+    })?;
+    let path_eq = |a: &syn::Path, b: &syn::Path| quote!(#a).to_string() == quote!(#b).to_string();
+    let mut restore_order = Vec::new();
+    for path in map.restore_order.iter().flat_map(|paths| paths.iter()) {
+        let index = registers
+            .iter()
+            .position(|register| path_eq(register, path))
+            .ok_or_else(|| {
+                syn::Error::new_spanned(path, "register not in the registers of the map")
+            })?;
+        if restore_order.contains(&index) {
+            return Err(syn::Error::new_spanned(
+                path,
+                "register already in the restore order",
+            ));
+        }
+        restore_order.push(index);
+    }
+    let (impl_gen, type_gen, where_gen) = &ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_gen device_register::RegisterMap for #name #type_gen #where_gen {
+            type Address = #ty;
+            type Raw = #raw;
+            type Values = [Option<#raw>; #len];
+
+            const NAME: &'static str = stringify!(#name);
+
+            const REGISTERS: &'static [device_register::RegisterInfo<#ty, #raw>] = &[
+                #(device_register::RegisterInfo::new::<#registers>()),*
+            ];
+            const EMPTY: Self::Values = [#(#empty),*];
+            const RESTORE_ORDER: &'static [usize] = &[#(#restore_order),*];
+        }
+
+        impl<I> device_register::MapAccess<I> for #name
+        where
+            I: device_register::RegisterInterface<#first, #ty>,
+            #(I: device_register::RegisterInterface<
+                #rest,
+                #ty,
+                Error = <I as device_register::RegisterInterface<#first, #ty>>::Error,
+            >,)*
+            #(#raw: From<#registers>,)*
+            #(<#registers as device_register::Register>::Protection: device_register::Unlock<
+                I,
+                <I as device_register::RegisterInterface<#first, #ty>>::Error,
+            >,)*
+        {
+            type Error = <I as device_register::RegisterInterface<#first, #ty>>::Error;
+
+            fn read_raw(iface: &mut I, index: usize) -> Result<#raw, Self::Error> {
+                match index {
+                    #(#indices => Ok(
+                        device_register::RegisterInterface::<#registers, #ty>::read_register(iface)?.into()
+                    ),)*
+                    _ => panic!("register index {} out of bounds for {}", index, stringify!(#name)),
+                }
+            }
+
+            fn write_raw(iface: &mut I, index: usize, raw: #raw) -> Result<(), Self::Error> {
+                match index {
+                    #(#indices => {
+                        <<#registers as device_register::Register>::Protection as device_register::Unlock<
+                            I,
+                            Self::Error,
+                        >>::unlock(iface)?;
+                        device_register::RegisterInterface::<#registers, #ty>::write_register(
+                            iface,
+                            &<#registers>::from(raw),
+                        )
+                    })*
+                    _ => panic!("register index {} out of bounds for {}", index, stringify!(#name)),
+                }
+            }
+        }
+    })
+}
+
+/// Create a register map, the list of all the registers of a device
+#[proc_macro_derive(RegisterMap, attributes(register_map))]
+pub fn register_map(input: TokenStream) -> TokenStream {
+    // Parse the representation
+    let ast = syn::parse(input).unwrap();
+
+    // Build the impl
+    impl_register_map(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The arguments passed to the field_enum helper attribute
+#[derive(darling::FromDeriveInput)]
+#[darling(attributes(field_enum), supports(enum_unit))]
+struct FieldEnum {
+    /// The number of bits of the field, defaults to the bits required by the largest value
+    width: Option<u32>,
+}
+
+fn impl_field_enum(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let args = FieldEnum::from_derive_input(ast)?;
+    let variants = match &ast.data {
+        syn::Data::Enum(data) => &data.variants,
+        _ => unreachable!("only unit enums are supported"),
+    };
+
+    // Evaluates the discriminants, which must be integer literals or implicit
+    let mut values = Vec::new();
+    let mut next = 0u64;
+    for variant in variants {
+        let value = match &variant.discriminant {
+            Some((
+                _,
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(int),
+                    ..
+                }),
+            )) => int.base10_parse()?,
+            Some((_, expr)) => {
+                return Err(syn::Error::new_spanned(
+                    expr,
+                    "the value of a variant must be an integer literal",
+                ))
+            }
+            None => next,
+        };
+        values.push(value);
+        next = value + 1;
+    }
+
+    let max = values.iter().copied().max().unwrap_or_default();
+    let width = args.width.unwrap_or(u64::BITS - max.leading_zeros());
+    if width >= 64 || max >> width != 0 {
+        return Err(syn::Error::new_spanned(
+            name,
+            "the values of the enum must fit in the width of the field",
+        ));
+    }
+    let complete = values.len() as u64 == 1 << width;
+
+    let variants: Vec<_> = variants.iter().map(|variant| &variant.ident).collect();
+    let (impl_gen, type_gen, where_gen) = &ast.generics.split_for_impl();
+    let try_decode = quote! {
+        match bits {
+            #(#values => Ok(Self::#variants),)*
+            bits => Err(device_register::InvalidValue { register, field, bits }),
+        }
+    };
+    let (decoded, decode) = if complete {
+        let decode = quote! {
+            match bits {
+                #(#values => Self::#variants,)*
+                _ => unreachable!("the value of {}.{} does not fit in the field", register, field),
+            }
+        };
+        (quote!(Self), decode)
+    } else {
+        (
+            quote!(Result<Self, device_register::InvalidValue>),
+            try_decode.clone(),
+        )
+    };
+    // The smallest unsigned integer holding the field, the values that are not a variant are returned as the error
+    let raw = quote::format_ident!("u{}", width.next_power_of_two().max(8));
+    Ok(quote! {
+        impl #impl_gen ::core::convert::TryFrom<#raw> for #name #type_gen #where_gen {
+            type Error = #raw;
+
+            fn try_from(value: #raw) -> Result<Self, Self::Error> {
+                match value as u64 {
+                    #(#values => Ok(Self::#variants),)*
+                    _ => Err(value),
+                }
+            }
+        }
+
+        impl #impl_gen device_register::FieldEnum for #name #type_gen #where_gen {
+            const WIDTH: u32 = #width;
+            type Decoded = #decoded;
+
+            fn decode(register: &'static str, field: &'static str, bits: u64) -> Self::Decoded {
+                #decode
+            }
+
+            fn try_decode(
+                register: &'static str,
+                field: &'static str,
+                bits: u64,
+            ) -> Result<Self, device_register::InvalidValue> {
+                #try_decode
+            }
+
+            fn bits(self) -> u64 {
+                match self {
+                    #(Self::#variants => #values,)*
+                }
+            }
+        }
+    })
+}
+
+/// Create an enumeration of the values of a field, referenced by the fields of the registers with `enum`
+#[proc_macro_derive(FieldEnum, attributes(field_enum))]
+pub fn field_enum(input: TokenStream) -> TokenStream {
+    // Parse the representation
+    let ast = syn::parse(input).unwrap();
+
+    // Build the impl
+    impl_field_enum(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The arguments passed to the maybe_async attribute
+#[derive(darling::FromMeta)]
+struct MaybeAsync {
+    /// The name of the blocking module
+    blocking: syn::Ident,
+}
+
+/// Converts an async module to its blocking version
+struct Blocking;
+
+/// Returns the blocking crate of an async crate, e.g. `device_register` for `device_register_async`
+fn blocking_crate(ident: &syn::Ident) -> Option<syn::Ident> {
+    let name = match ident.to_string().as_str() {
+        "device_register_async" => "device_register",
+        "embedded_hal_async" => "embedded_hal",
+        _ => return None,
+    };
+    Some(syn::Ident::new(name, ident.span()))
+}
+
+/// Returns `T` if the type is `impl Future<Output = T>`, with any other bound like `Send`
+fn future_output(ty: &syn::Type) -> Option<syn::Type> {
+    let syn::Type::ImplTrait(ty) = ty else {
+        return None;
+    };
+    ty.bounds.iter().find_map(|bound| {
+        let syn::TypeParamBound::Trait(bound) = bound else {
+            return None;
+        };
+        let future = bound.path.segments.last()?;
+        if future.ident != "Future" {
+            return None;
+        }
+        let syn::PathArguments::AngleBracketed(args) = &future.arguments else {
+            return None;
+        };
+        args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Binding(binding) if binding.ident == "Output" => {
+                Some(binding.ty.clone())
+            }
+            _ => None,
+        })
+    })
+}
+
+impl syn::visit_mut::VisitMut for Blocking {
+    fn visit_signature_mut(&mut self, sig: &mut syn::Signature) {
+        sig.asyncness = None;
+        syn::visit_mut::visit_signature_mut(self, sig);
+    }
+
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        if let Some(output) = future_output(ty) {
+            *ty = output;
+        }
+        syn::visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        match expr {
+            syn::Expr::Await(inner) => *expr = (*inner.base).clone(),
+            syn::Expr::Async(inner) => {
+                *expr = syn::Expr::Block(syn::ExprBlock {
+                    attrs: inner.attrs.clone(),
+                    label: None,
+                    block: inner.block.clone(),
+                })
+            }
+            syn::Expr::Closure(inner) => inner.asyncness = None,
+            _ => {}
+        }
+        syn::visit_mut::visit_expr_mut(self, expr);
+    }
+
+    fn visit_path_mut(&mut self, path: &mut syn::Path) {
+        if let Some(first) = path.segments.first_mut() {
+            if let Some(ident) = blocking_crate(&first.ident) {
+                first.ident = ident;
+            }
+        }
+        syn::visit_mut::visit_path_mut(self, path);
+    }
+
+    fn visit_use_path_mut(&mut self, path: &mut syn::UsePath) {
+        if let Some(ident) = blocking_crate(&path.ident) {
+            path.ident = ident;
+        }
+        syn::visit_mut::visit_use_path_mut(self, path);
+    }
+
+    fn visit_use_name_mut(&mut self, name: &mut syn::UseName) {
+        if let Some(ident) = blocking_crate(&name.ident) {
+            name.ident = ident;
+        }
+    }
+}
+
+fn impl_maybe_async(
+    args: syn::AttributeArgs,
+    module: syn::ItemMod,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let args = MaybeAsync::from_list(&args)?;
+    if module.content.is_none() {
+        return Err(syn::Error::new_spanned(
+            &module,
+            "maybe_async requires an inline module",
+        ));
+    }
+    let mut blocking = module.clone();
+    blocking.ident = args.blocking;
+    syn::visit_mut::visit_item_mod_mut(&mut Blocking, &mut blocking);
+    Ok(quote! {
+        #module
+        #blocking
+    })
+}
+
+/// Generates the blocking version of a module written against `device-register-async`, so a driver is written once for both.
+/// The module is kept as is, and the blocking module named by `blocking` is generated next to it,
+/// where the functions, closures and blocks are not async, the `.await` are removed, the `impl Future<Output = T>` are replaced by `T`,
+/// and the crates `device_register_async` and `embedded_hal_async` are replaced by `device_register` and `embedded_hal`.
+///
+/// ```ignore
+/// #[maybe_async(blocking = "blocking")]
+/// pub mod asynch {
+///     use device_register_async::*;
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn maybe_async(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(args as syn::AttributeArgs);
+    let module = syn::parse_macro_input!(input as syn::ItemMod);
+    impl_maybe_async(args, module)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,169 @@
+//! Arguments of the command line of the shell
+
+use std::{fmt, str::FromStr, time::Duration};
+
+/// Usage of the command line
+pub const USAGE: &str = "\
+usage: device-register-shell --map FILE [--peripheral NAME] [--backend BACKEND] [--address-size BYTES] [--interval MS] [--doc]
+
+  --map FILE            register map, a YAML or TOML register map or an SVD file
+  --peripheral NAME     peripheral of the SVD file, defaults to the first one
+  --backend BACKEND     backend used to access the device, defaults to mock
+                          mock               simulated device
+                          i2c:<bus>:<addr>   device at addr on the I2C bus, e.g. i2c:1:0x48 or i2c:/dev/i2c-1:0x48
+                          spi:<dev>          SPI device, e.g. spi:/dev/spidev0.0
+  --address-size BYTES  bytes of the register addresses sent by the i2c and spi backends, defaults to 1
+  --interval MS         polling interval of watch in milliseconds, defaults to 100
+  --doc                 prints a markdown reference of the register map instead of starting the shell";
+
+/// Backend accessing the device, the I2C and SPI backends require the `linux` feature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// The [`Simulator`](crate::Simulator)
+    Mock,
+
+    /// Device on an I2C bus of Linux
+    I2c {
+        /// Path of the bus, e.g. `/dev/i2c-1`
+        bus: String,
+
+        /// The 7 bits address of the device
+        address: u8,
+    },
+
+    /// SPI device of Linux
+    Spi {
+        /// Path of the device, e.g. `/dev/spidev0.0`
+        device: String,
+    },
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(backend: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid backend {}", backend);
+        match backend.split_once(':') {
+            None if backend == "mock" => Ok(Backend::Mock),
+            Some(("i2c", rest)) => {
+                let (bus, address) = rest.rsplit_once(':').ok_or_else(invalid)?;
+                if bus.is_empty() {
+                    return Err(invalid());
+                }
+                // A bus number is the path of the bus
+                let bus = match bus.parse::<u32>() {
+                    Ok(number) => format!("/dev/i2c-{}", number),
+                    Err(_) => bus.to_string(),
+                };
+                let address = match address.strip_prefix("0x") {
+                    Some(hex) => u8::from_str_radix(hex, 16),
+                    None => address.parse(),
+                }
+                .ok()
+                .filter(|address| *address <= 0x7F)
+                .ok_or_else(invalid)?;
+                Ok(Backend::I2c { bus, address })
+            }
+            Some(("spi", device)) if !device.is_empty() => Ok(Backend::Spi {
+                device: device.to_string(),
+            }),
+            _ => Err(format!("unknown backend {}", backend)),
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backend::Mock => write!(f, "mock"),
+            Backend::I2c { bus, address } => write!(f, "i2c:{}:{:#04x}", bus, address),
+            Backend::Spi { device } => write!(f, "spi:{}", device),
+        }
+    }
+}
+
+/// Arguments of the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    /// Path of the register map
+    pub map: String,
+
+    /// Peripheral of the SVD file, the first one if not set
+    pub peripheral: Option<String>,
+
+    /// Backend accessing the device
+    pub backend: Backend,
+
+    /// Bytes of the register addresses sent by the I2C and SPI backends
+    pub address_size: usize,
+
+    /// Polling interval of `watch`
+    pub interval: Duration,
+
+    /// If the markdown reference of the map was requested with `--doc`
+    pub doc: bool,
+
+    /// If the usage was requested with `--help`
+    pub help: bool,
+}
+
+impl Args {
+    /// Parses the arguments, without the name of the program
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut map = None;
+        let mut peripheral = None;
+        let mut backend = Backend::Mock;
+        let mut address_size = 1;
+        let mut interval = Duration::from_millis(100);
+        let mut doc = false;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+            match arg.as_str() {
+                "--map" => map = Some(value()?),
+                "--peripheral" => peripheral = Some(value()?),
+                "--backend" => backend = value()?.parse()?,
+                "--address-size" => {
+                    let size = value()?;
+                    address_size = size
+                        .parse()
+                        .ok()
+                        .filter(|size| (1..=8).contains(size))
+                        .ok_or(format!("invalid address size {}", size))?;
+                }
+                "--interval" => {
+                    let ms = value()?;
+                    let ms = ms.parse().map_err(|_| format!("invalid interval {}", ms))?;
+                    interval = Duration::from_millis(ms);
+                }
+                "--doc" => doc = true,
+                "--help" | "-h" => {
+                    return Ok(Args {
+                        map: String::new(),
+                        peripheral,
+                        backend,
+                        address_size,
+                        interval,
+                        doc,
+                        help: true,
+                    })
+                }
+                arg => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+
+        Ok(Args {
+            map: map.ok_or("missing --map")?,
+            peripheral,
+            backend,
+            address_size,
+            interval,
+            doc,
+            help: false,
+        })
+    }
+}
@@ -0,0 +1,599 @@
+#![doc = include_str!("../README.md")]
+#![deny(unsafe_code, missing_docs)]
+
+use std::{fmt, io, thread, time::Duration};
+
+use device_register::{export::NumericAddress, Permissions, RawRegisterAccess, RegisterMap};
+use device_register_codegen::{Access, FieldDescription, Peripheral, RegisterDescription};
+
+pub mod args;
+mod simulator;
+pub use simulator::Simulator;
+
+/// Errors of the commands of the shell
+#[derive(Debug)]
+pub enum Error {
+    /// The command is not known
+    UnknownCommand(String),
+
+    /// The arguments of the command are invalid
+    Usage(&'static str),
+
+    /// No register with this name
+    UnknownRegister(String),
+
+    /// No field with this name in the register
+    UnknownField(String),
+
+    /// The value could not be parsed or does not fit in the register
+    InvalidValue(String),
+
+    /// The register does not allow this access
+    Denied(String),
+
+    /// Error returned by the backend
+    Backend(String),
+
+    /// Error writing the output
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownCommand(command) => {
+                write!(f, "unknown command `{}`, see `help`", command)
+            }
+            Error::Usage(usage) => write!(f, "usage: {}", usage),
+            Error::UnknownRegister(name) => write!(f, "unknown register `{}`", name),
+            Error::UnknownField(name) => write!(f, "unknown field `{}`", name),
+            Error::InvalidValue(value) => write!(f, "invalid value `{}`", value),
+            Error::Denied(message) => write!(f, "access denied, {}", message),
+            Error::Backend(err) => write!(f, "backend error: {}", err),
+            Error::Io(err) => write!(f, "io error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// What the shell should do after a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Wait for the next command
+    Continue,
+
+    /// Exit the shell
+    Exit,
+}
+
+const HELP: &str = "\
+read REG               reads a register and decodes its fields
+write REG=VALUE        writes a register
+write REG.FIELD=VALUE  edits a field of a register
+dump                   reads all the registers without read side effects
+watch REG [COUNT]      polls a register and prints its value when it changes
+list                   lists the registers
+help                   prints this message
+exit                   exits the shell";
+
+/// Interactive shell accessing the registers of a device through the backend `B`
+pub struct Shell<B> {
+    base_address: u64,
+    registers: Vec<RegisterDescription>,
+    backend: B,
+    interval: Duration,
+}
+
+impl<B> Shell<B>
+where
+    B: RawRegisterAccess,
+    B::Error: fmt::Display,
+{
+    /// Creates a shell for the registers of the peripheral
+    pub fn new(peripheral: &Peripheral, backend: B) -> Self {
+        Self {
+            base_address: peripheral.base_address.unwrap_or(0),
+            registers: peripheral.registers.clone(),
+            backend,
+            interval: Duration::from_millis(100),
+        }
+    }
+
+    /// Creates a shell for the registers of the map
+    pub fn from_map<M>(backend: B) -> Self
+    where
+        M: RegisterMap,
+        M::Address: NumericAddress,
+    {
+        Self::new(&describe::<M>(), backend)
+    }
+
+    /// Sets the polling interval of `watch`, defaults to 100ms
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns a reference to the backend
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns a mutable reference to the backend
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Returns the backend
+    pub fn into_backend(self) -> B {
+        self.backend
+    }
+
+    /// Executes a command line, the result of the command is written to `out`
+    pub fn execute(&mut self, line: &str, out: &mut impl io::Write) -> Result<Flow, Error> {
+        let mut args = line.split_whitespace();
+        let command = match args.next() {
+            Some(command) => command,
+            None => return Ok(Flow::Continue),
+        };
+        let args: Vec<&str> = args.collect();
+        match (command, &args[..]) {
+            ("read", [name]) => self.read(name, out)?,
+            ("read", _) => return Err(Error::Usage("read REG")),
+            ("write", [assignment]) => self.write(assignment)?,
+            ("write", _) => return Err(Error::Usage("write REG=VALUE or REG.FIELD=VALUE")),
+            ("dump", []) => self.dump(out)?,
+            ("dump", _) => return Err(Error::Usage("dump")),
+            ("watch", [name]) => self.watch(name, None, out)?,
+            ("watch", [name, count]) => {
+                let count = parse_value(count)?;
+                self.watch(name, Some(count), out)?
+            }
+            ("watch", _) => return Err(Error::Usage("watch REG [COUNT]")),
+            ("list", _) => self.list(out)?,
+            ("help", _) => writeln!(out, "{}", HELP)?,
+            ("exit" | "quit", _) => return Ok(Flow::Exit),
+            (command, _) => return Err(Error::UnknownCommand(command.to_string())),
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn register(&self, name: &str) -> Result<&RegisterDescription, Error> {
+        self.registers
+            .iter()
+            .find(|register| register.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::UnknownRegister(name.to_string()))
+    }
+
+    fn address(&self, register: &RegisterDescription) -> u64 {
+        self.base_address + register.offset
+    }
+
+    fn read_value(&mut self, register: &RegisterDescription) -> Result<u64, Error> {
+        if !readable(register.access) {
+            return Err(Error::Denied(format!("{} can't be read", register.name)));
+        }
+        let mut buf = vec![0; bytes(register)];
+        let len = self
+            .backend
+            .read_raw(self.address(register), &mut buf)
+            .map_err(|err| Error::Backend(err.to_string()))?;
+        Ok(buf[..len]
+            .iter()
+            .fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+    }
+
+    fn write_value(&mut self, register: &RegisterDescription, value: u64) -> Result<(), Error> {
+        let len = bytes(register);
+        let data: Vec<u8> = (0..len).rev().map(|i| (value >> (i * 8)) as u8).collect();
+        self.backend
+            .write_raw(self.address(register), &data)
+            .map_err(|err| Error::Backend(err.to_string()))
+    }
+
+    fn read(&mut self, name: &str, out: &mut impl io::Write) -> Result<(), Error> {
+        let register = self.register(name)?.clone();
+        let value = self.read_value(&register)?;
+        writeln!(
+            out,
+            "{} @ {:#04x}: {}",
+            register.name,
+            self.address(&register),
+            Hex(value, register.size)
+        )?;
+        for field in &register.fields {
+            let field_value = (value >> field.offset) & mask(field.width);
+            writeln!(
+                out,
+                "  {} {} = {}",
+                field.name,
+                Bits(field),
+                Hex(field_value, field.width)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, assignment: &str) -> Result<(), Error> {
+        let (target, value) = assignment
+            .split_once('=')
+            .ok_or(Error::Usage("write REG=VALUE or REG.FIELD=VALUE"))?;
+        let value = parse_value(value)?;
+        let (name, field) = match target.split_once('.') {
+            Some((name, field)) => (name, Some(field)),
+            None => (target, None),
+        };
+        let register = self.register(name)?.clone();
+
+        let value = match field {
+            None => {
+                if !writable(register.access) {
+                    return Err(Error::Denied(format!(
+                        "{} can't be written, edit its fields instead",
+                        register.name
+                    )));
+                }
+                if value > mask(register.size) {
+                    return Err(Error::InvalidValue(value.to_string()));
+                }
+                value
+            }
+            Some(field) => {
+                let field = register
+                    .fields
+                    .iter()
+                    .find(|f| f.name.eq_ignore_ascii_case(field))
+                    .ok_or_else(|| Error::UnknownField(field.to_string()))?;
+                if !field.access.is_writable() || !register.access.is_writable() {
+                    return Err(Error::Denied(format!(
+                        "{}.{} can't be written",
+                        register.name, field.name
+                    )));
+                }
+                if value > mask(field.width) {
+                    return Err(Error::InvalidValue(value.to_string()));
+                }
+                let field_mask = mask(field.width) << field.offset;
+                let edit = |current: u64| (current & !field_mask) | (value << field.offset);
+                match register.access {
+                    // Write only registers can't be read back, the other fields are set to their reset value
+                    Access::WriteOnly => edit(register.reset.unwrap_or(0)),
+                    _ => return self.edit_value(&register, edit),
+                }
+            }
+        };
+        self.write_value(&register, value)
+    }
+
+    /// Edits the register with `f` in a single access of the backend, the editable registers can't be read or written on their own
+    fn edit_value(
+        &mut self,
+        register: &RegisterDescription,
+        f: impl Fn(u64) -> u64,
+    ) -> Result<(), Error> {
+        let mut buf = vec![0; bytes(register)];
+        let address = self.address(register);
+        self.backend
+            .edit_raw(address, &mut buf, &mut |bytes| {
+                let current = bytes
+                    .iter()
+                    .fold(0, |value, byte| (value << 8) | u64::from(*byte));
+                let value = f(current);
+                for (i, byte) in bytes.iter_mut().rev().enumerate() {
+                    *byte = (value >> (i * 8)) as u8;
+                }
+            })
+            .map_err(|err| Error::Backend(err.to_string()))
+    }
+
+    fn dump(&mut self, out: &mut impl io::Write) -> Result<(), Error> {
+        let registers: Vec<_> = self
+            .registers
+            .iter()
+            .filter(|register| readable(register.access) && !register.read_side_effects)
+            .cloned()
+            .collect();
+        for register in registers {
+            let value = self.read_value(&register)?;
+            writeln!(
+                out,
+                "{} @ {:#04x}: {}",
+                register.name,
+                self.address(&register),
+                Hex(value, register.size)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn watch(
+        &mut self,
+        name: &str,
+        count: Option<u64>,
+        out: &mut impl io::Write,
+    ) -> Result<(), Error> {
+        let register = self.register(name)?.clone();
+        let mut previous = None;
+        let mut polls = 0;
+        while count != Some(polls) {
+            if polls != 0 {
+                thread::sleep(self.interval);
+            }
+            let value = self.read_value(&register)?;
+            match previous {
+                None => writeln!(
+                    out,
+                    "{} @ {:#04x}: {}",
+                    register.name,
+                    self.address(&register),
+                    Hex(value, register.size)
+                )?,
+                Some(previous) if previous != value => writeln!(
+                    out,
+                    "{} @ {:#04x}: {} -> {}",
+                    register.name,
+                    self.address(&register),
+                    Hex(previous, register.size),
+                    Hex(value, register.size)
+                )?,
+                Some(_) => {}
+            }
+            out.flush()?;
+            previous = Some(value);
+            polls += 1;
+        }
+        Ok(())
+    }
+
+    fn list(&mut self, out: &mut impl io::Write) -> Result<(), Error> {
+        for register in &self.registers {
+            writeln!(
+                out,
+                "{} @ {:#04x} {} {} bits{}",
+                register.name,
+                self.address(register),
+                register.access.short_name(),
+                register.size,
+                if register.read_side_effects {
+                    ", read side effects"
+                } else {
+                    ""
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Describes a register map as a peripheral, so it can be used by the shell.
+/// The registers with unknown permissions, like the [`Permissions::NONE`] of the manual registers, are skipped since they can't be accessed safely
+pub fn describe<M>() -> Peripheral
+where
+    M: RegisterMap,
+    M::Address: NumericAddress,
+{
+    let size = std::mem::size_of::<M::Raw>() as u32 * 8;
+    let registers = M::REGISTERS
+        .iter()
+        .filter_map(|info| {
+            let access = access(info.permissions)?;
+            Some(RegisterDescription {
+                name: info.name.to_string(),
+                description: Some(info.description.to_string()).filter(|d| !d.is_empty()),
+                offset: info.address.value(),
+                size,
+                access,
+                reset: info.reset,
+                read_side_effects: info.read_side_effects,
+                fields: info
+                    .fields
+                    .iter()
+                    .map(|field| FieldDescription {
+                        name: field.name.to_string(),
+                        description: Some(field.description.to_string()).filter(|d| !d.is_empty()),
+                        offset: field.offset,
+                        width: field.width,
+                        access: self::access(field.permissions).unwrap_or(access),
+                    })
+                    .collect(),
+            })
+        })
+        .collect();
+    Peripheral {
+        name: M::NAME.to_string(),
+        description: None,
+        base_address: None,
+        registers,
+    }
+}
+
+/// Writes a reference of the peripheral as markdown, in the format of [`export::markdown`](device_register::export::markdown).
+/// Contains a table of all the registers followed by a section per register
+/// with its description, a diagram of the bits and a table of its fields.
+pub fn reference<W: fmt::Write>(peripheral: &Peripheral, w: &mut W) -> fmt::Result {
+    let base_address = peripheral.base_address.unwrap_or(0);
+    let reset = |value: Option<u64>, bits: u32| {
+        value.map_or("-".to_string(), |value| Hex(value, bits).to_string())
+    };
+    writeln!(w, "# {}", peripheral.name)?;
+    writeln!(w)?;
+    if let Some(description) = &peripheral.description {
+        writeln!(w, "{}", description)?;
+        writeln!(w)?;
+    }
+    writeln!(w, "| Register | Address | Access | Reset | Description |")?;
+    writeln!(w, "|---|---|---|---|---|")?;
+    for register in &peripheral.registers {
+        writeln!(
+            w,
+            "| [{}](#{}) | {:#04x} | {} | {} | {} |",
+            register.name,
+            register.name.to_ascii_lowercase(),
+            base_address + register.offset,
+            register.access.short_name(),
+            reset(register.reset, register.size),
+            Cell(register.description.as_deref().unwrap_or_default())
+        )?;
+    }
+
+    for register in &peripheral.registers {
+        writeln!(w)?;
+        writeln!(w, "## {}", register.name)?;
+        writeln!(w)?;
+        if let Some(description) = &register.description {
+            writeln!(w, "{}", description)?;
+            writeln!(w)?;
+        }
+        writeln!(
+            w,
+            "Address: `{:#04x}`, access: `{}`, reset: `{}`{}",
+            base_address + register.offset,
+            register.access.short_name(),
+            reset(register.reset, register.size),
+            if register.read_side_effects {
+                ", reading has side effects"
+            } else {
+                ""
+            }
+        )?;
+        if register.fields.is_empty() {
+            continue;
+        }
+
+        let mut fields: Vec<_> = register.fields.iter().collect();
+        fields.sort_by_key(|field| field.offset);
+        writeln!(w)?;
+        writeln!(w, "```text")?;
+        let layout = fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.offset, field.width));
+        device_register::export::diagram(w, layout, register.size)?;
+        writeln!(w, "```")?;
+        writeln!(w)?;
+        writeln!(w, "| Bits | Field | Access | Reset | Description |")?;
+        writeln!(w, "|---|---|---|---|---|")?;
+        for field in fields.iter().rev() {
+            let value = register
+                .reset
+                .map(|reset| reset.checked_shr(field.offset).unwrap_or(0) & mask(field.width));
+            if field.width == 1 {
+                write!(w, "| {} ", field.offset)?;
+            } else {
+                write!(w, "| {}:{} ", field.offset + field.width - 1, field.offset)?;
+            }
+            writeln!(
+                w,
+                "| {} | {} | {} | {} |",
+                field.name,
+                field.access.short_name(),
+                reset(value, field.width),
+                Cell(field.description.as_deref().unwrap_or_default())
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// The access of the permissions, `None` if they are unknown
+fn access(permissions: Permissions) -> Option<Access> {
+    match (
+        permissions.is_readable(),
+        permissions.is_writable(),
+        permissions.is_editable(),
+    ) {
+        (false, false, false) => None,
+        (true, false, false) => Some(Access::ReadOnly),
+        (false, true, false) => Some(Access::WriteOnly),
+        (false, false, true) => Some(Access::EditOnly),
+        (true, false, true) => Some(Access::ReadEdit),
+        _ => Some(Access::ReadWrite),
+    }
+}
+
+/// If the whole register can be read, edit only registers are only read to be edited
+fn readable(access: Access) -> bool {
+    matches!(
+        access,
+        Access::ReadOnly | Access::ReadEdit | Access::ReadWrite
+    )
+}
+
+/// If the whole register can be written, the editable registers only allow to write their fields
+fn writable(access: Access) -> bool {
+    matches!(access, Access::WriteOnly | Access::ReadWrite)
+}
+
+fn bytes(register: &RegisterDescription) -> usize {
+    (register.size as usize).div_ceil(8)
+}
+
+fn mask(width: u32) -> u64 {
+    if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+/// Parses a decimal, hexadecimal (`0x`) or binary (`0b`) value
+fn parse_value(value: &str) -> Result<u64, Error> {
+    let parsed = if let Some(hex) = value.strip_prefix("0x") {
+        u64::from_str_radix(&hex.replace('_', ""), 16)
+    } else if let Some(bin) = value.strip_prefix("0b") {
+        u64::from_str_radix(&bin.replace('_', ""), 2)
+    } else {
+        value.replace('_', "").parse()
+    };
+    parsed.map_err(|_| Error::InvalidValue(value.to_string()))
+}
+
+/// Formats a value as hexadecimal, padded to the number of bits
+struct Hex(u64, u32);
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = (self.1 as usize).div_ceil(4) + 2;
+        write!(f, "{:#0width$x}", self.0, width = width)
+    }
+}
+
+/// Formats the bits of a field, e.g. `[11:10]`
+struct Bits<'a>(&'a FieldDescription);
+
+impl fmt::Display for Bits<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.width == 1 {
+            write!(f, "[{}]", self.0.offset)
+        } else {
+            write!(
+                f,
+                "[{}:{}]",
+                self.0.offset + self.0.width - 1,
+                self.0.offset
+            )
+        }
+    }
+}
+
+/// Escapes a string for a cell of a markdown table, on a single line
+struct Cell<'a>(&'a str);
+
+impl fmt::Display for Cell<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.replace('|', "\\|").replace('\n', " "))
+    }
+}
@@ -0,0 +1,150 @@
+//! I2C and SPI devices of Linux used by the `i2c` and `spi` backends, implementing the traits of `embedded-hal` 1.0
+//! over the `i2cdev` and `spidev` character devices
+
+use std::{fmt, io, path::Path};
+
+use embedded_hal::{i2c, spi};
+use i2cdev::{
+    core::{I2CMessage, I2CTransfer},
+    linux::{LinuxI2CBus, LinuxI2CMessage},
+};
+use spidev::{Spidev, SpidevTransfer};
+
+// Error numbers of Linux reported by the I2C adapters, see the i2c fault codes of the kernel documentation
+const EAGAIN: i32 = 11;
+const ENXIO: i32 = 6;
+const EPROTO: i32 = 71;
+const EREMOTEIO: i32 = 121;
+
+/// Error of an access to a character device, its kind is taken from the error number
+#[derive(Debug)]
+pub struct HostError(pub io::Error);
+
+impl fmt::Display for HostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for HostError {}
+
+impl i2c::Error for HostError {
+    fn kind(&self) -> i2c::ErrorKind {
+        match self.0.raw_os_error() {
+            Some(ENXIO) => i2c::ErrorKind::NoAcknowledge(i2c::NoAcknowledgeSource::Address),
+            Some(EREMOTEIO) => i2c::ErrorKind::NoAcknowledge(i2c::NoAcknowledgeSource::Unknown),
+            Some(EAGAIN) => i2c::ErrorKind::ArbitrationLoss,
+            Some(EPROTO) => i2c::ErrorKind::Bus,
+            _ => i2c::ErrorKind::Other,
+        }
+    }
+}
+
+impl spi::Error for HostError {
+    fn kind(&self) -> spi::ErrorKind {
+        spi::ErrorKind::Other
+    }
+}
+
+/// I2C bus of Linux, e.g. `/dev/i2c-1`, each transaction addresses the device given to it
+pub struct HostI2c {
+    bus: LinuxI2CBus,
+}
+
+impl HostI2c {
+    /// Opens the I2C bus at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, HostError> {
+        let bus = LinuxI2CBus::new(path).map_err(|err| HostError(err.into()))?;
+        Ok(Self { bus })
+    }
+}
+
+impl i2c::ErrorType for HostI2c {
+    type Error = HostError;
+}
+
+impl i2c::I2c for HostI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let mut messages: Vec<_> = operations
+            .iter_mut()
+            .map(|operation| match operation {
+                i2c::Operation::Read(buf) => LinuxI2CMessage::read(buf),
+                i2c::Operation::Write(buf) => LinuxI2CMessage::write(buf),
+            })
+            .map(|message| message.with_address(address.into()))
+            .collect();
+        self.bus
+            .transfer(&mut messages)
+            .map_err(|err| HostError(err.into()))?;
+        Ok(())
+    }
+}
+
+/// SPI device of Linux, e.g. `/dev/spidev0.0`, the chip select is asserted by the kernel for each transaction
+pub struct HostSpi {
+    spi: Spidev,
+}
+
+impl HostSpi {
+    /// Opens the SPI device at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, HostError> {
+        let spi = Spidev::open(path).map_err(HostError)?;
+        Ok(Self { spi })
+    }
+}
+
+impl spi::ErrorType for HostSpi {
+    type Error = HostError;
+}
+
+impl spi::SpiDevice for HostSpi {
+    fn transaction(
+        &mut self,
+        operations: &mut [spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        // The words sent by the transfers in place, the kernel needs them in another buffer
+        let sent: Vec<Vec<u8>> = operations
+            .iter()
+            .filter_map(|operation| match operation {
+                spi::Operation::TransferInPlace(buf) => Some(buf.to_vec()),
+                _ => None,
+            })
+            .collect();
+        let mut sent = sent.iter();
+        let mut transfers = Vec::with_capacity(operations.len());
+        for operation in operations.iter_mut() {
+            match operation {
+                spi::Operation::Read(buf) => transfers.push(SpidevTransfer::read(buf)),
+                spi::Operation::Write(buf) => transfers.push(SpidevTransfer::write(buf)),
+                spi::Operation::Transfer(read, write) => {
+                    // The longest buffer is completed by a read or a write on its own
+                    let len = read.len().min(write.len());
+                    let (read, read_rest) = read.split_at_mut(len);
+                    let (write, write_rest) = write.split_at(len);
+                    transfers.push(SpidevTransfer::read_write(write, read));
+                    if !read_rest.is_empty() {
+                        transfers.push(SpidevTransfer::read(read_rest));
+                    }
+                    if !write_rest.is_empty() {
+                        transfers.push(SpidevTransfer::write(write_rest));
+                    }
+                }
+                spi::Operation::TransferInPlace(buf) => {
+                    let write = sent.next().expect("one buffer per transfer in place");
+                    transfers.push(SpidevTransfer::read_write(write, buf));
+                }
+                spi::Operation::DelayNs(ns) => {
+                    let us = ns.div_ceil(1000).try_into().unwrap_or(u16::MAX);
+                    transfers.push(SpidevTransfer::delay(us));
+                }
+            }
+        }
+        self.spi
+            .transfer_multiple(&mut transfers)
+            .map_err(HostError)
+    }
+}
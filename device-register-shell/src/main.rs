@@ -0,0 +1,124 @@
+use std::{
+    env, fmt, fs,
+    io::{self, BufRead, Write},
+    path::Path,
+    process,
+};
+
+#[cfg(feature = "linux")]
+mod linux;
+
+#[cfg(feature = "linux")]
+use device_register::hal::{I2cBus, SpiInterface};
+use device_register::RawRegisterAccess;
+use device_register_codegen::{map, svd, Device, Peripheral};
+use device_register_shell::{
+    args::{Args, Backend, USAGE},
+    Flow, Shell, Simulator,
+};
+
+/// Loads the peripheral from the register map or the SVD file
+fn load(path: &str, peripheral: Option<&str>) -> Result<Peripheral, String> {
+    let content = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    let extension = Path::new(path).extension().and_then(|ext| ext.to_str());
+    let device: Device = match extension {
+        Some("yaml" | "yml") => map::parse_yaml(&content),
+        Some("toml") => map::parse_toml(&content),
+        Some("svd" | "xml") => svd::parse(&content),
+        _ => return Err(format!("unsupported register map {}", path)),
+    }
+    .map_err(|err| format!("{}: {}", path, err))?;
+
+    let mut peripherals = device.peripherals.into_iter();
+    match peripheral {
+        Some(name) => peripherals
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .ok_or(format!("unknown peripheral {}", name)),
+        None => peripherals
+            .next()
+            .ok_or(format!("{} has no peripheral", path)),
+    }
+}
+
+/// Reads the commands from stdin until the end of the input or `exit`
+fn run<B>(mut shell: Shell<B>) -> io::Result<()>
+where
+    B: RawRegisterAccess,
+    B::Error: fmt::Display,
+{
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut line = String::new();
+    loop {
+        write!(stdout, "> ")?;
+        stdout.flush()?;
+        line.clear();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        match shell.execute(&line, &mut stdout) {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Exit) => return Ok(()),
+            Err(err) => eprintln!("error: {}", err),
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("error: {}\n\n{}", err, USAGE);
+        process::exit(2);
+    });
+    if args.help {
+        println!("{}", USAGE);
+        return;
+    }
+    let peripheral = load(&args.map, args.peripheral.as_deref()).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    });
+    if args.doc {
+        let mut markdown = String::new();
+        device_register_shell::reference(&peripheral, &mut markdown).unwrap();
+        print!("{}", markdown);
+        return;
+    }
+
+    let result = match &args.backend {
+        Backend::Mock => {
+            let simulator = Simulator::new(&peripheral);
+            run(Shell::new(&peripheral, simulator).with_interval(args.interval))
+        }
+        #[cfg(feature = "linux")]
+        Backend::I2c { bus, address } => {
+            let i2c = linux::HostI2c::open(bus).unwrap_or_else(|err| {
+                eprintln!("error: {}: {}", bus, err);
+                process::exit(1);
+            });
+            let bus = I2cBus::new(i2c).with_address_size(args.address_size);
+            let device = device_register::Device::new(bus, *address);
+            run(Shell::new(&peripheral, device).with_interval(args.interval))
+        }
+        #[cfg(feature = "linux")]
+        Backend::Spi { device } => {
+            let spi = linux::HostSpi::open(device).unwrap_or_else(|err| {
+                eprintln!("error: {}: {}", device, err);
+                process::exit(1);
+            });
+            let device = SpiInterface::new(spi).with_address_size(args.address_size);
+            run(Shell::new(&peripheral, device).with_interval(args.interval))
+        }
+        #[cfg(not(feature = "linux"))]
+        backend => {
+            eprintln!(
+                "error: the backend {} requires the linux feature of device-register-shell",
+                backend
+            );
+            process::exit(2);
+        }
+    };
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,79 @@
+//! Mock of a device, used to try the shell and the drivers without hardware
+
+use std::{collections::BTreeMap, convert::Infallible};
+
+use device_register::{RawAccessError, RawRegisterAccess};
+use device_register_codegen::Peripheral;
+
+/// Simulated device that stores the value of each register of a peripheral.
+/// The registers start at their reset value, or 0 if unknown.
+/// Accesses are not restricted by the permissions of the registers, like a real device would ignore them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Simulator {
+    registers: BTreeMap<u64, Vec<u8>>,
+}
+
+impl Simulator {
+    /// Creates a simulator of the registers of the peripheral
+    pub fn new(peripheral: &Peripheral) -> Self {
+        let base_address = peripheral.base_address.unwrap_or(0);
+        let registers = peripheral
+            .registers
+            .iter()
+            .map(|register| {
+                let len = (register.size as usize).div_ceil(8);
+                let reset = register.reset.unwrap_or(0);
+                let bytes = (0..len).rev().map(|i| (reset >> (i * 8)) as u8).collect();
+                (base_address + register.offset, bytes)
+            })
+            .collect();
+        Self { registers }
+    }
+
+    /// Returns the bytes of the register at `address`
+    pub fn get(&self, address: u64) -> Option<&[u8]> {
+        self.registers.get(&address).map(Vec::as_slice)
+    }
+
+    /// Sets the bytes of the register at `address`, e.g. to simulate a change of the device
+    pub fn set(&mut self, address: u64, bytes: &[u8]) {
+        self.registers.insert(address, bytes.to_vec());
+    }
+}
+
+impl RawRegisterAccess for Simulator {
+    type Error = RawAccessError<Infallible>;
+
+    fn read_raw(&mut self, address: u64, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let bytes = self
+            .registers
+            .get(&address)
+            .ok_or(RawAccessError::UnknownAddress(address))?;
+        let buf = match buf.get_mut(..bytes.len()) {
+            Some(buf) => buf,
+            None => {
+                return Err(RawAccessError::Size {
+                    expected: bytes.len(),
+                    actual: buf.len(),
+                })
+            }
+        };
+        buf.copy_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn write_raw(&mut self, address: u64, data: &[u8]) -> Result<(), Self::Error> {
+        let bytes = self
+            .registers
+            .get_mut(&address)
+            .ok_or(RawAccessError::UnknownAddress(address))?;
+        if bytes.len() != data.len() {
+            return Err(RawAccessError::Size {
+                expected: bytes.len(),
+                actual: data.len(),
+            });
+        }
+        bytes.copy_from_slice(data);
+        Ok(())
+    }
+}
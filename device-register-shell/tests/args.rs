@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use device_register_shell::args::{Args, Backend};
+
+fn parse(args: &[&str]) -> Result<Args, String> {
+    Args::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn parse_args() {
+    let args = parse(&["--map", "tmp117.yaml"]).unwrap();
+    assert_eq!(
+        args,
+        Args {
+            map: "tmp117.yaml".to_string(),
+            peripheral: None,
+            backend: Backend::Mock,
+            address_size: 1,
+            interval: Duration::from_millis(100),
+            doc: false,
+            help: false,
+        }
+    );
+
+    let args = parse(&[
+        "--map",
+        "device.svd",
+        "--peripheral",
+        "TMP117",
+        "--backend",
+        "spi:/dev/spidev0.0",
+        "--address-size",
+        "2",
+        "--interval",
+        "10",
+        "--doc",
+    ])
+    .unwrap();
+    assert_eq!(args.peripheral.as_deref(), Some("TMP117"));
+    assert_eq!(args.address_size, 2);
+    assert_eq!(args.interval, Duration::from_millis(10));
+    assert!(args.doc);
+
+    assert!(parse(&["--help"]).unwrap().help);
+    assert_eq!(parse(&[]), Err("missing --map".to_string()));
+    assert_eq!(
+        parse(&["--map"]),
+        Err("missing value for --map".to_string())
+    );
+    assert_eq!(
+        parse(&["--map", "a.yaml", "--address-size", "9"]),
+        Err("invalid address size 9".to_string())
+    );
+    assert_eq!(
+        parse(&["--map", "a.yaml", "--verbose"]),
+        Err("unknown argument --verbose".to_string())
+    );
+}
+
+#[test]
+fn select_backend() {
+    assert_eq!("mock".parse(), Ok(Backend::Mock));
+    assert_eq!(
+        "i2c:1:0x48".parse(),
+        Ok(Backend::I2c {
+            bus: "/dev/i2c-1".to_string(),
+            address: 0x48
+        })
+    );
+    assert_eq!(
+        "i2c:/dev/i2c-3:72".parse(),
+        Ok(Backend::I2c {
+            bus: "/dev/i2c-3".to_string(),
+            address: 0x48
+        })
+    );
+    assert_eq!(
+        "spi:/dev/spidev0.0".parse(),
+        Ok(Backend::Spi {
+            device: "/dev/spidev0.0".to_string()
+        })
+    );
+
+    // The address is a 7 bits address, and the bus and the device are required
+    for backend in ["i2c:1:0x80", "i2c:1", "i2c::0x48", "i2c:1:0xZZ"] {
+        assert_eq!(
+            backend.parse::<Backend>(),
+            Err(format!("invalid backend {}", backend))
+        );
+    }
+    for backend in ["spi:", "usb:0", "mock:1"] {
+        assert_eq!(
+            backend.parse::<Backend>(),
+            Err(format!("unknown backend {}", backend))
+        );
+    }
+
+    let args = Args::parse(["--map", "a.yaml", "--backend", "i2c:1:0x48"].map(String::from));
+    assert_eq!(args.unwrap().backend.to_string(), "i2c:/dev/i2c-1:0x48");
+}
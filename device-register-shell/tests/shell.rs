@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use device_register::{
+    EORegister, RERegister, RORegister, RWRegister, RawMap, Register, RegisterInterface,
+    RegisterMap, Unprotected,
+};
+use device_register_codegen::map;
+use device_register_shell::{describe, reference, Error, Flow, Shell, Simulator};
+
+const MAP: &str = r#"
+name: TMP117
+size: 16
+registers:
+  - name: TEMP_RESULT
+    address: 0x00
+    access: ro
+    reset: 0x8000
+  - name: CONFIGURATION
+    address: 0x01
+    reset: 0x0220
+    fields:
+      - name: AVG
+        offset: 5
+        width: 2
+      - name: MOD
+        offset: 10
+        width: 2
+      - name: DATA_READY
+        offset: 13
+        access: ro
+  - name: STATUS
+    address: 0x02
+    access: ro
+    read_side_effects: true
+"#;
+
+fn run<B>(shell: &mut Shell<B>, line: &str) -> Result<String, Error>
+where
+    B: device_register::RawRegisterAccess,
+    B::Error: std::fmt::Display,
+{
+    let mut out = Vec::new();
+    shell.execute(line, &mut out)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn shell_commands() {
+    let device = map::parse_yaml(MAP).unwrap();
+    let peripheral = &device.peripherals[0];
+    let mut shell = Shell::new(peripheral, Simulator::new(peripheral));
+
+    assert_eq!(
+        run(&mut shell, "read configuration").unwrap(),
+        "CONFIGURATION @ 0x01: 0x0220\n  AVG [6:5] = 0x1\n  MOD [11:10] = 0x0\n  DATA_READY [13] = 0x0\n"
+    );
+
+    run(&mut shell, "write CONFIGURATION.MOD=0b01").unwrap();
+    assert_eq!(shell.backend().get(0x01), Some(&[0x06, 0x20][..]));
+    run(&mut shell, "write CONFIGURATION=0x1234").unwrap();
+    assert_eq!(shell.backend().get(0x01), Some(&[0x12, 0x34][..]));
+
+    assert_eq!(
+        run(&mut shell, "dump").unwrap(),
+        "TEMP_RESULT @ 0x00: 0x8000\nCONFIGURATION @ 0x01: 0x1234\n"
+    );
+
+    shell.backend_mut().set(0x00, &[0x0C, 0x80]);
+    assert_eq!(
+        run(&mut shell, "watch TEMP_RESULT 2").unwrap(),
+        "TEMP_RESULT @ 0x00: 0x0c80\n"
+    );
+
+    assert!(matches!(
+        run(&mut shell, "write TEMP_RESULT=1"),
+        Err(Error::Denied(_))
+    ));
+    assert!(matches!(
+        run(&mut shell, "write CONFIGURATION.DATA_READY=1"),
+        Err(Error::Denied(_))
+    ));
+    assert!(matches!(
+        run(&mut shell, "write CONFIGURATION.AVG=4"),
+        Err(Error::InvalidValue(_))
+    ));
+    assert!(matches!(
+        run(&mut shell, "read NOPE"),
+        Err(Error::UnknownRegister(_))
+    ));
+    assert!(matches!(
+        run(&mut shell, "frobnicate"),
+        Err(Error::UnknownCommand(_))
+    ));
+    assert_eq!(shell.execute("exit", &mut Vec::new()).unwrap(), Flow::Exit);
+}
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = 0x00, reset = 0x12)]
+pub struct Status(pub u8);
+impl From<Status> for u8 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Status {
+    fn from(val: u8) -> Self {
+        Status(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x01, fields(EN(offset = 0)))]
+pub struct Control(pub u8);
+impl From<Control> for u8 {
+    fn from(val: Control) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Control {
+    fn from(val: u8) -> Self {
+        Control(val)
+    }
+}
+
+// Manual register, its permissions are unknown
+#[derive(Debug, Clone, Copy)]
+pub struct Legacy(pub u8);
+impl Register for Legacy {
+    type Address = u8;
+    type Protection = Unprotected;
+    const ADDRESS: Self::Address = 0x02;
+    const NAME: &'static str = "Legacy";
+}
+impl From<Legacy> for u8 {
+    fn from(val: Legacy) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Legacy {
+    fn from(val: u8) -> Self {
+        Legacy(val)
+    }
+}
+
+#[derive(RegisterMap)]
+#[register_map(raw = "u8", registers(Status, Control, Legacy))]
+pub struct DeviceMap;
+
+#[test]
+fn shell_register_map() {
+    let peripheral = describe::<DeviceMap>();
+    let mut shell = Shell::from_map::<DeviceMap>(Simulator::new(&peripheral));
+
+    run(&mut shell, "write control.en=1").unwrap();
+    assert_eq!(
+        run(&mut shell, "list").unwrap(),
+        "Status @ 0x00 ro 8 bits\nControl @ 0x01 rw 8 bits\n"
+    );
+    assert!(matches!(
+        run(&mut shell, "read Legacy"),
+        Err(Error::UnknownRegister(_))
+    ));
+    assert_eq!(
+        run(&mut shell, "dump").unwrap(),
+        "Status @ 0x00: 0x12\nControl @ 0x01: 0x01\n"
+    );
+}
+
+#[test]
+fn shell_reference() {
+    let device = map::parse_yaml(MAP).unwrap();
+    let mut markdown = String::new();
+    reference(&device.peripherals[0], &mut markdown).unwrap();
+    assert_eq!(
+        markdown,
+        r#"# TMP117
+
+| Register | Address | Access | Reset | Description |
+|---|---|---|---|---|
+| [TEMP_RESULT](#temp_result) | 0x00 | ro | 0x8000 |  |
+| [CONFIGURATION](#configuration) | 0x01 | rw | 0x0220 |  |
+| [STATUS](#status) | 0x02 | ro | - |  |
+
+## TEMP_RESULT
+
+Address: `0x00`, access: `ro`, reset: `0x8000`
+
+## CONFIGURATION
+
+Address: `0x01`, access: `rw`, reset: `0x0220`
+
+```text
+ 15 14 13 12 11 10  9  8  7  6  5  4  3  2  1  0
+|     |a |  | MOD |        | AVG |              |
+a: DATA_READY
+```
+
+| Bits | Field | Access | Reset | Description |
+|---|---|---|---|---|
+| 13 | DATA_READY | ro | 0x0 |  |
+| 11:10 | MOD | rw | 0x0 |  |
+| 6:5 | AVG | rw | 0x1 |  |
+
+## STATUS
+
+Address: `0x02`, access: `ro`, reset: `-`, reading has side effects
+"#
+    );
+}
+
+#[derive(Debug, Clone, Copy, EORegister)]
+#[register(addr = 0x10, fields(EN(offset = 0), GAIN(offset = 4, width = 4)))]
+pub struct Trim(pub u8);
+impl From<Trim> for u8 {
+    fn from(val: Trim) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Trim {
+    fn from(val: u8) -> Self {
+        Trim(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RERegister)]
+#[register(addr = 0x11, fields(EN(offset = 0)))]
+pub struct Mode(pub u8);
+impl From<Mode> for u8 {
+    fn from(val: Mode) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Mode {
+    fn from(val: u8) -> Self {
+        Mode(val)
+    }
+}
+
+#[derive(RegisterMap)]
+#[register_map(raw = "u8", registers(Trim, Mode))]
+pub struct EditMap;
+
+// Mock of a driver storing the registers by address
+#[derive(Default)]
+pub struct Driver {
+    pub registers: HashMap<u8, u8>,
+}
+
+impl<R> RegisterInterface<R, u8> for Driver
+where
+    R: Register<Address = u8> + Clone + From<u8>,
+    u8: From<R>,
+{
+    type Error = std::convert::Infallible;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        Ok(self.registers.get(&R::ADDRESS).copied().unwrap_or(0).into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.registers.insert(R::ADDRESS, register.clone().into());
+        Ok(())
+    }
+}
+
+#[test]
+fn shell_raw_map_edit() {
+    let mut driver = Driver::default();
+    driver.registers.insert(0x10, 0x01);
+    driver.registers.insert(0x11, 0x80);
+    let mut shell = Shell::from_map::<EditMap>(RawMap::<EditMap, _>::new(driver));
+
+    // The editable registers are edited through the raw map, the other fields are kept
+    run(&mut shell, "write TRIM.GAIN=0xA").unwrap();
+    run(&mut shell, "write MODE.EN=1").unwrap();
+    let registers = &shell.backend().inner().registers;
+    assert_eq!(registers.get(&0x10), Some(&0xA1));
+    assert_eq!(registers.get(&0x11), Some(&0x81));
+
+    assert_eq!(
+        run(&mut shell, "read MODE").unwrap(),
+        "Mode @ 0x11: 0x81\n  EN [0] = 0x1\n"
+    );
+    assert!(matches!(
+        run(&mut shell, "write TRIM=1"),
+        Err(Error::Denied(_))
+    ));
+    assert!(matches!(
+        run(&mut shell, "read TRIM"),
+        Err(Error::Denied(_))
+    ));
+}
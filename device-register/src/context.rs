@@ -0,0 +1,144 @@
+//! Errors identifying the register and the operation that failed
+
+use core::fmt;
+
+use crate::{
+    export::NumericAddress, EditRegister, EditableRegister, ReadRegister, ReadableRegister,
+    WritableRegister, WriteRegister,
+};
+
+/// Operation performed on a register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Read of the register
+    Read,
+
+    /// Write of the register
+    Write,
+
+    /// Read-edit-write of the register
+    Edit,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operation = match self {
+            Operation::Read => "read",
+            Operation::Write => "write",
+            Operation::Edit => "edit",
+        };
+        f.write_str(operation)
+    }
+}
+
+/// Error of an access with the register and the operation that failed, returned by [`Context`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessError<E> {
+    /// The name of the register
+    pub register: &'static str,
+
+    /// The address of the register
+    pub address: u64,
+
+    /// The operation that failed
+    pub operation: Operation,
+
+    /// The error returned by the access
+    pub error: E,
+}
+
+impl<E> AccessError<E> {
+    /// Creates the error of the `operation` on the register `R`
+    pub fn new<R>(operation: Operation, error: E) -> Self
+    where
+        R: crate::Register,
+        R::Address: NumericAddress,
+    {
+        Self {
+            register: R::NAME,
+            address: R::ADDRESS.value(),
+            operation,
+            error,
+        }
+    }
+}
+
+impl<E> fmt::Display for AccessError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to {} register {} at {:#x}: {}",
+            self.operation, self.register, self.address, self.error
+        )
+    }
+}
+
+/// Wrapper of an interface adding the register and the operation to the errors of the accesses, as an [`AccessError`].
+/// Its `read`, `write` and `edit` methods take precedence over the ones of the traits, e.g. `Context::new(iface).read()`.
+/// The addresses of the registers must implement [`NumericAddress`].
+#[derive(Debug)]
+pub struct Context<I> {
+    iface: I,
+}
+
+impl<I> Context<I> {
+    /// Creates the wrapper of the interface
+    pub const fn new(iface: I) -> Self {
+        Self { iface }
+    }
+
+    /// Returns a reference to the inner interface
+    pub const fn inner(&self) -> &I {
+        &self.iface
+    }
+
+    /// Returns a mutable reference to the inner interface
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iface
+    }
+
+    /// Returns the inner interface
+    pub fn into_inner(self) -> I {
+        self.iface
+    }
+
+    /// Reads a register, see [`ReadRegister::read`]
+    pub fn read<R, A>(&mut self) -> Result<R, AccessError<<I as ReadRegister<R, A>>::Error>>
+    where
+        R: ReadableRegister<Address = A>,
+        A: NumericAddress,
+        I: ReadRegister<R, A>,
+    {
+        self.iface
+            .read()
+            .map_err(|err| AccessError::new::<R>(Operation::Read, err))
+    }
+
+    /// Writes a register, see [`WriteRegister::write`]
+    pub fn write<R, A>(&mut self, register: R) -> Result<(), AccessError<I::Error>>
+    where
+        R: WritableRegister<Address = A>,
+        A: NumericAddress,
+        I: WriteRegister<R, A>,
+    {
+        self.iface
+            .write(register)
+            .map_err(|err| AccessError::new::<R>(Operation::Write, err))
+    }
+
+    /// Edits a register, see [`EditRegister::edit`]
+    pub fn edit<R, A, F>(&mut self, f: F) -> Result<(), AccessError<I::Error>>
+    where
+        R: EditableRegister<Address = A>,
+        A: NumericAddress,
+        I: EditRegister<R, A>,
+        for<'w> F: FnOnce(&'w mut R),
+    {
+        self.iface
+            .edit(f)
+            .map_err(|err| AccessError::new::<R>(Operation::Edit, err))
+    }
+}
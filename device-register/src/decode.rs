@@ -0,0 +1,42 @@
+//! Decoding of the registers read from the device, which can contain invalid encodings like reserved values
+
+use core::fmt;
+
+use crate::InvalidValue;
+
+/// Hook decoding a register read by the interface, called through [`Register::decode`](crate::Register::decode) by the derived registers.
+/// Implemented by the derive, which rejects the reserved values of the fields referencing a [`FieldEnum`](crate::FieldEnum).
+/// Set `#[register(try_from_raw)]` to implement it instead, e.g. to validate a bitfield.
+/// The registers implemented manually don't need it, they accept every value unless they override [`Register::decode`](crate::Register::decode).
+pub trait TryFromRaw: Sized {
+    /// Decodes the register, as built by the interface from the raw value read from the device
+    fn try_from_raw(register: Self) -> Result<Self, InvalidValue>;
+}
+
+/// Error returned by the read and edit accesses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterError<E> {
+    /// Error returned by the interface
+    Bus(E),
+
+    /// The register read contains an invalid encoding
+    Decode(InvalidValue),
+}
+
+impl<E> From<InvalidValue> for RegisterError<E> {
+    fn from(err: InvalidValue) -> Self {
+        RegisterError::Decode(err)
+    }
+}
+
+impl<E> fmt::Display for RegisterError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegisterError::Bus(err) => write!(f, "bus error: {}", err),
+            RegisterError::Decode(err) => write!(f, "decode error: {}", err),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Support of several identical devices sharing the same bus, each selected by its address
+
+use core::cell::RefCell;
+
+use crate::{Register, RegisterInterface};
+
+/// Traits that define how to read and write the registers of a device on a bus shared by multiple devices.
+/// Similar to [`RegisterInterface`], but the device is selected on each call using its address on the bus, e.g. its I2C address.
+/// Note that those functions should mostly just be implemented and not used since they are not bound by Read/Write/Edit permission.
+pub trait BusInterface<R, A>
+where
+    R: Register<Address = A>,
+{
+    /// The address of a device on the bus
+    type DeviceAddress;
+
+    /// Error type returned by the bus
+    type Error;
+
+    /// Reads a register of the device and returns it
+    fn read_register(&mut self, device: &Self::DeviceAddress) -> Result<R, Self::Error>;
+
+    /// Writes a register to the device
+    fn write_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        register: &R,
+    ) -> Result<(), Self::Error>;
+
+    /// Reads the register of the device as many times as the length of `values`, see [`RegisterInterface::read_fifo_register`]
+    fn read_fifo_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        values: &mut [R],
+    ) -> Result<(), Self::Error> {
+        for value in values {
+            *value = self.read_register(device)?;
+        }
+        Ok(())
+    }
+}
+
+/// The bus is borrowed for each access, allows sharing it between multiple [`Device`], like the `RefCellDevice` of `embedded-hal-bus`.
+/// Panics if the bus is already borrowed
+impl<B, R, A> BusInterface<R, A> for &RefCell<B>
+where
+    R: Register<Address = A>,
+    B: BusInterface<R, A>,
+{
+    type DeviceAddress = B::DeviceAddress;
+    type Error = B::Error;
+
+    fn read_register(&mut self, device: &Self::DeviceAddress) -> Result<R, Self::Error> {
+        self.borrow_mut().read_register(device)
+    }
+
+    fn write_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        register: &R,
+    ) -> Result<(), Self::Error> {
+        self.borrow_mut().write_register(device, register)
+    }
+
+    fn read_fifo_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        values: &mut [R],
+    ) -> Result<(), Self::Error> {
+        self.borrow_mut().read_fifo_register(device, values)
+    }
+}
+
+impl<B, R, A> BusInterface<R, A> for &mut B
+where
+    R: Register<Address = A>,
+    B: BusInterface<R, A>,
+{
+    type DeviceAddress = B::DeviceAddress;
+    type Error = B::Error;
+
+    fn read_register(&mut self, device: &Self::DeviceAddress) -> Result<R, Self::Error> {
+        (**self).read_register(device)
+    }
+
+    fn write_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        register: &R,
+    ) -> Result<(), Self::Error> {
+        (**self).write_register(device, register)
+    }
+
+    fn read_fifo_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        values: &mut [R],
+    ) -> Result<(), Self::Error> {
+        (**self).read_fifo_register(device, values)
+    }
+}
+
+/// Handle to a device on a shared bus, implements [`RegisterInterface`] by selecting the device on each access.
+/// Allows to use one register set for multiple identical devices, e.g. `Device::new(&bus, 0x48)` and `Device::new(&bus, 0x49)`
+/// with `bus` a `RefCell` of the bus implementing [`BusInterface`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Device<Bus, Addr> {
+    bus: Bus,
+    address: Addr,
+}
+
+impl<Bus, Addr> Device<Bus, Addr> {
+    /// Creates the handle of the device at `address` on the bus
+    pub const fn new(bus: Bus, address: Addr) -> Self {
+        Self { bus, address }
+    }
+
+    /// Returns the address of the device on the bus
+    pub const fn address(&self) -> &Addr {
+        &self.address
+    }
+
+    /// Returns a reference to the bus
+    pub const fn bus(&self) -> &Bus {
+        &self.bus
+    }
+
+    /// Returns a mutable reference to the bus
+    pub fn bus_mut(&mut self) -> &mut Bus {
+        &mut self.bus
+    }
+
+    /// Returns a mutable reference to the bus and the address of the device
+    pub fn parts_mut(&mut self) -> (&mut Bus, &Addr) {
+        (&mut self.bus, &self.address)
+    }
+
+    /// Returns the bus and the address of the device
+    pub fn release(self) -> (Bus, Addr) {
+        (self.bus, self.address)
+    }
+}
+
+impl<Bus, Addr, R, A> RegisterInterface<R, A> for Device<Bus, Addr>
+where
+    R: Register<Address = A>,
+    Bus: BusInterface<R, A, DeviceAddress = Addr>,
+{
+    type Error = Bus::Error;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let (bus, address) = self.parts_mut();
+        bus.read_register(address)
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let (bus, address) = self.parts_mut();
+        bus.write_register(address, register)
+    }
+
+    fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        let (bus, address) = self.parts_mut();
+        bus.read_fifo_register(address, values)
+    }
+}
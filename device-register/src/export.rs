@@ -0,0 +1,446 @@
+//! Export of the register maps to machine readable descriptions and to a human readable reference.
+//!
+//! Allows the rust definitions to be the source of truth of the registers of a device.
+//! The descriptions are written to any [`fmt::Write`], so they can be generated from a test or a build script.
+//! The JSON description uses the same format as the register maps of the `device-register-codegen` crate.
+//! The markdown reference can be included in the documentation of the driver with `#[doc = include_str!(..)]`.
+
+use core::{fmt, mem};
+
+use crate::{Permissions, RegisterInfo, RegisterMap};
+
+/// Trait of an address that has a numeric value, required by the tools that need to display or parse the addresses.
+/// Implement it for the newtypes used as addresses.
+pub trait NumericAddress {
+    /// The numeric value of the address
+    fn value(&self) -> u64;
+}
+
+macro_rules! impl_numeric_address {
+    ($($ty:ty),*) => {
+        $(
+            impl NumericAddress for $ty {
+                fn value(&self) -> u64 {
+                    *self as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_address!(u8, u16, u32, u64, usize);
+
+/// Short name of the permissions, matches the access of the register maps of `device-register-codegen`.
+/// `None` if the permissions are unknown, like the [`Permissions::NONE`] of the manual registers
+fn access(permissions: Permissions) -> Option<&'static str> {
+    match (
+        permissions.is_readable(),
+        permissions.is_writable(),
+        permissions.is_editable(),
+    ) {
+        (false, false, false) => None,
+        (true, false, false) => Some("ro"),
+        (false, true, false) => Some("wo"),
+        (false, false, true) => Some("eo"),
+        (true, false, true) => Some("re"),
+        _ => Some("rw"),
+    }
+}
+
+/// Long name of the permissions, as used in SVD files, `None` if the permissions are unknown.
+/// Editing a register requires to both read and write it
+fn svd_access(permissions: Permissions) -> Option<&'static str> {
+    let edit = permissions.is_editable();
+    match (
+        permissions.is_readable() || edit,
+        permissions.is_writable() || edit,
+    ) {
+        (false, false) => None,
+        (true, false) => Some("read-only"),
+        (false, true) => Some("write-only"),
+        (true, true) => Some("read-write"),
+    }
+}
+
+/// Escapes a string for JSON
+struct Json<'a>(&'a str);
+
+impl fmt::Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a string for XML
+struct Xml<'a>(&'a str);
+
+impl fmt::Display for Xml<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '&' => f.write_str("&amp;")?,
+                c => fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a string for a cell of a markdown table, on a single line
+struct Cell<'a>(&'a str);
+
+impl fmt::Display for Cell<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '|' => f.write_str("\\|")?,
+                '\n' => f.write_str(" ")?,
+                c => fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the description of the register map as JSON
+pub fn json<M, W>(w: &mut W) -> fmt::Result
+where
+    M: RegisterMap,
+    M::Address: NumericAddress,
+    W: fmt::Write,
+{
+    write!(
+        w,
+        "{{\"name\":\"{}\",\"size\":{},\"registers\":[",
+        M::NAME,
+        mem::size_of::<M::Raw>() * 8
+    )?;
+    for (i, info) in M::REGISTERS.iter().enumerate() {
+        if i != 0 {
+            write!(w, ",")?;
+        }
+        write!(
+            w,
+            "{{\"name\":\"{}\",\"address\":{}",
+            info.name,
+            info.address.value()
+        )?;
+        if let Some(access) = access(info.permissions) {
+            write!(w, ",\"access\":\"{}\"", access)?;
+        }
+        write!(w, ",\"read_side_effects\":{}", info.read_side_effects)?;
+        if !info.description.is_empty() {
+            write!(w, ",\"description\":\"{}\"", Json(info.description))?;
+        }
+        if let Some(reset) = info.reset {
+            write!(w, ",\"reset\":{}", reset)?;
+        }
+        if !info.fields.is_empty() {
+            write!(w, ",\"fields\":[")?;
+            for (i, field) in info.fields.iter().enumerate() {
+                if i != 0 {
+                    write!(w, ",")?;
+                }
+                write!(
+                    w,
+                    "{{\"name\":\"{}\",\"offset\":{},\"width\":{}",
+                    field.name, field.offset, field.width
+                )?;
+                if let Some(access) = access(field.permissions) {
+                    write!(w, ",\"access\":\"{}\"", access)?;
+                }
+                if !field.description.is_empty() {
+                    write!(w, ",\"description\":\"{}\"", Json(field.description))?;
+                }
+                write!(w, "}}")?;
+            }
+            write!(w, "]")?;
+        }
+        write!(w, "}}")?;
+    }
+    write!(w, "]}}")
+}
+
+/// Writes the description of the register map as a CMSIS-SVD file.
+/// The map is described as a single peripheral at the address 0
+pub fn svd<M, W>(w: &mut W) -> fmt::Result
+where
+    M: RegisterMap,
+    M::Address: NumericAddress,
+    W: fmt::Write,
+{
+    let size = mem::size_of::<M::Raw>() * 8;
+    writeln!(w, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
+    writeln!(w, "<device schemaVersion=\"1.3\">")?;
+    writeln!(w, "  <name>{}</name>", M::NAME)?;
+    writeln!(w, "  <size>{}</size>", size)?;
+    writeln!(w, "  <peripherals>")?;
+    writeln!(w, "    <peripheral>")?;
+    writeln!(w, "      <name>{}</name>", M::NAME)?;
+    writeln!(w, "      <baseAddress>0x0</baseAddress>")?;
+    writeln!(w, "      <registers>")?;
+    for info in M::REGISTERS {
+        writeln!(w, "        <register>")?;
+        writeln!(w, "          <name>{}</name>", info.name)?;
+        if !info.description.is_empty() {
+            writeln!(
+                w,
+                "          <description>{}</description>",
+                Xml(info.description)
+            )?;
+        }
+        writeln!(
+            w,
+            "          <addressOffset>{:#x}</addressOffset>",
+            info.address.value()
+        )?;
+        if let Some(access) = svd_access(info.permissions) {
+            writeln!(w, "          <access>{}</access>", access)?;
+        }
+        if let Some(reset) = info.reset {
+            writeln!(w, "          <resetValue>{:#x}</resetValue>", reset)?;
+        }
+        if info.read_side_effects {
+            writeln!(w, "          <readAction>modify</readAction>")?;
+        }
+        if !info.fields.is_empty() {
+            writeln!(w, "          <fields>")?;
+            for field in info.fields {
+                writeln!(w, "            <field>")?;
+                writeln!(w, "              <name>{}</name>", field.name)?;
+                if !field.description.is_empty() {
+                    writeln!(
+                        w,
+                        "              <description>{}</description>",
+                        Xml(field.description)
+                    )?;
+                }
+                writeln!(w, "              <bitOffset>{}</bitOffset>", field.offset)?;
+                writeln!(w, "              <bitWidth>{}</bitWidth>", field.width)?;
+                if let Some(access) = svd_access(field.permissions) {
+                    writeln!(w, "              <access>{}</access>", access)?;
+                }
+                writeln!(w, "            </field>")?;
+            }
+            writeln!(w, "          </fields>")?;
+        }
+        writeln!(w, "        </register>")?;
+    }
+    writeln!(w, "      </registers>")?;
+    writeln!(w, "    </peripheral>")?;
+    writeln!(w, "  </peripherals>")?;
+    writeln!(w, "</device>")
+}
+
+/// Writes a reference of the register map as markdown.
+/// Contains a table of all the registers followed by a section per register
+/// with its description, a diagram of the bits and a table of its fields.
+pub fn markdown<M, W>(w: &mut W) -> fmt::Result
+where
+    M: RegisterMap,
+    M::Address: NumericAddress,
+    W: fmt::Write,
+{
+    let size = mem::size_of::<M::Raw>() as u32 * 8;
+    writeln!(w, "# {}", M::NAME)?;
+    writeln!(w)?;
+    writeln!(w, "| Register | Address | Access | Reset | Description |")?;
+    writeln!(w, "|---|---|---|---|---|")?;
+    for info in M::REGISTERS {
+        writeln!(
+            w,
+            "| [{}](#{}) | {:#04x} | {} | {} | {} |",
+            info.name,
+            Anchor(info.name),
+            info.address.value(),
+            access(info.permissions).unwrap_or("-"),
+            Reset(info.reset.map(|reset| (reset, size))),
+            Cell(info.description)
+        )?;
+    }
+
+    for info in M::REGISTERS {
+        writeln!(w)?;
+        markdown_register(w, info, size)?;
+    }
+    Ok(())
+}
+
+fn markdown_register<A, Raw, W>(w: &mut W, info: &RegisterInfo<A, Raw>, size: u32) -> fmt::Result
+where
+    A: NumericAddress,
+    W: fmt::Write,
+{
+    writeln!(w, "## {}", info.name)?;
+    writeln!(w)?;
+    if !info.description.is_empty() {
+        writeln!(w, "{}", info.description)?;
+        writeln!(w)?;
+    }
+    writeln!(
+        w,
+        "Address: `{:#04x}`, access: `{}`, reset: `{}`{}",
+        info.address.value(),
+        access(info.permissions).unwrap_or("-"),
+        Reset(info.reset.map(|reset| (reset, size))),
+        if info.read_side_effects {
+            ", reading has side effects"
+        } else {
+            ""
+        }
+    )?;
+    if info.fields.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(w)?;
+    writeln!(w, "```text")?;
+    let fields = info
+        .fields
+        .iter()
+        .map(|field| (field.name, field.offset, field.width));
+    diagram(w, fields, size)?;
+    writeln!(w, "```")?;
+    writeln!(w)?;
+    writeln!(w, "| Bits | Field | Access | Reset | Description |")?;
+    writeln!(w, "|---|---|---|---|---|")?;
+    for field in info.fields.iter().rev() {
+        let msb = field.offset.saturating_add(field.width.saturating_sub(1));
+        if field.width == 1 {
+            write!(w, "| {} ", field.offset)?;
+        } else {
+            write!(w, "| {}:{} ", msb, field.offset)?;
+        }
+        writeln!(
+            w,
+            "| {} | {} | {} | {} |",
+            field.name,
+            access(field.permissions).unwrap_or("-"),
+            Reset(info.reset.map(|reset| (field.extract(reset), field.width))),
+            Cell(field.description)
+        )?;
+    }
+    Ok(())
+}
+
+/// Draws the layout of the fields of a register of `size` bits, the most significant bit first.
+/// The fields are given as their name, offset and width, ordered by offset like [`Register::FIELDS`](crate::Register::FIELDS).
+/// The fields whose name doesn't fit in their bits are labeled by a letter, followed by a legend of the full names, e.g.
+/// ```text
+///   7  6  5  4  3  2  1  0
+/// |     |  MODE  |  |a |  |
+/// a: ENABLE
+/// ```
+pub fn diagram<'a, W, I>(w: &mut W, fields: I, size: u32) -> fmt::Result
+where
+    W: fmt::Write,
+    I: IntoIterator<Item = (&'a str, u32, u32)>,
+    I::IntoIter: Clone + DoubleEndedIterator,
+{
+    let fields = fields.into_iter();
+    for bit in (0..size).rev() {
+        write!(w, "{:>3}", bit)?;
+    }
+    writeln!(w)?;
+
+    let mut labels = 0;
+    let mut bit = size;
+    while bit > 0 {
+        let field = fields
+            .clone()
+            .find(|&(_, offset, width)| offset < bit && bit <= offset.saturating_add(width));
+        let (name, width) = match field {
+            Some((name, offset, _)) => (name, bit - offset),
+            // Reserved bits are grouped up to the next field
+            None => {
+                let next = fields
+                    .clone()
+                    .map(|(_, offset, width)| offset.saturating_add(width))
+                    .filter(|&end| end < bit)
+                    .max()
+                    .unwrap_or(0);
+                ("", bit - next)
+            }
+        };
+        let space = (width * 3 - 1) as usize;
+        if name.len() <= space {
+            write!(w, "|{:^space$}", name, space = space)?;
+        } else {
+            write!(w, "|{:^space$}", Label(labels), space = space)?;
+            labels += 1;
+        }
+        bit -= width;
+    }
+    writeln!(w, "|")?;
+
+    // The fields are labeled in the order they are drawn
+    let labeled = fields.rev().filter(|&(name, offset, width)| {
+        let end = offset.saturating_add(width).min(size);
+        end > offset && name.len() > ((end - offset) * 3 - 1) as usize
+    });
+    for (label, (name, _, _)) in labeled.enumerate() {
+        writeln!(w, "{}: {}", Label(label), name)?;
+    }
+    Ok(())
+}
+
+/// Label of a field in a diagram, `a` to `z` then `aa` to `zz`, fits in the two columns of a single bit
+struct Label(usize);
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut label = [0; 2];
+        let len = if self.0 < 26 {
+            label[0] = b'a' + self.0 as u8;
+            1
+        } else {
+            label[0] = b'a' + (self.0 / 26 - 1).min(25) as u8;
+            label[1] = b'a' + (self.0 % 26) as u8;
+            2
+        };
+        let label = core::str::from_utf8(&label[..len]).unwrap_or_default();
+        f.pad(label)
+    }
+}
+
+/// Formats a heading as its anchor, in lower case
+struct Anchor<'a>(&'a str);
+
+impl fmt::Display for Anchor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            fmt::Write::write_char(f, c.to_ascii_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats an optional reset value as hexadecimal, padded to the number of bits
+struct Reset(Option<(u64, u32)>);
+
+impl fmt::Display for Reset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some((value, bits)) => {
+                write!(
+                    f,
+                    "{:#0width$x}",
+                    value,
+                    width = (bits as usize).div_ceil(4) + 2
+                )
+            }
+            None => f.write_str("-"),
+        }
+    }
+}
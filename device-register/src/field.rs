@@ -0,0 +1,50 @@
+//! Enumerations stored in the fields of the registers
+
+use core::fmt;
+
+/// Enumeration of the values of a field, implemented with the `FieldEnum` derive.
+/// Referenced by a field of a register with `enum`, which generates the accessors of the field on the register.
+pub trait FieldEnum: Sized {
+    /// The number of bits of the field
+    const WIDTH: u32;
+
+    /// Result of the decoding of the field.
+    /// `Self` if every value of the field is a variant, otherwise `Result<Self, InvalidValue>`
+    type Decoded;
+
+    /// Decodes the bits of the field `field` of the register `register`, the bits are already shifted to the offset 0
+    fn decode(register: &'static str, field: &'static str, bits: u64) -> Self::Decoded;
+
+    /// Decodes the bits like [`FieldEnum::decode`], but always returns a `Result`
+    fn try_decode(
+        register: &'static str,
+        field: &'static str,
+        bits: u64,
+    ) -> Result<Self, InvalidValue>;
+
+    /// Returns the bits of the variant
+    fn bits(self) -> u64;
+}
+
+/// Error returned when a field contains a value that is not a variant of its enumeration, e.g. a reserved value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InvalidValue {
+    /// The name of the register
+    pub register: &'static str,
+
+    /// The name of the field
+    pub field: &'static str,
+
+    /// The raw bits of the field
+    pub bits: u64,
+}
+
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid value {:#x} for field {} of register {}",
+            self.bits, self.field, self.register
+        )
+    }
+}
@@ -0,0 +1,37 @@
+//! FIFO registers, a single address read repeatedly to drain a buffer of the device, e.g. the samples of a sensor
+
+use crate::{Register, RegisterError, RegisterInterface};
+
+/// Trait of a FIFO register, each read pops the next value of the FIFO of the device.
+/// It is not a [`ReadableRegister`](crate::ReadableRegister) since reading it changes the state of the device,
+/// it is read in bulk with [`ReadFifo`] instead.
+pub trait FifoRegister: Register {}
+
+/// Trait to read the values of a FIFO register. Only a FIFO register can be read this way.
+pub trait ReadFifo<R, A>
+where
+    R: FifoRegister<Address = A>,
+{
+    /// Error type returned by reading the FIFO
+    type Error;
+
+    /// Reads as many values of the FIFO as the length of `values`, in the order they are popped
+    fn read_fifo(&mut self, values: &mut [R]) -> Result<(), Self::Error>;
+}
+
+impl<I, R, A> ReadFifo<R, A> for I
+where
+    R: FifoRegister<Address = A> + Clone,
+    I: RegisterInterface<R, A>,
+{
+    type Error = RegisterError<I::Error>;
+
+    fn read_fifo(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.read_fifo_register(values)
+            .map_err(RegisterError::Bus)?;
+        for value in values.iter_mut() {
+            *value = R::decode(value.clone())?;
+        }
+        Ok(())
+    }
+}
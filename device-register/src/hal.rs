@@ -0,0 +1,350 @@
+//! Interfaces over the blocking I2C and SPI traits of `embedded-hal` 1.0,
+//! e.g. to run the drivers on Linux hosts with the `I2cdev` and `SpidevDevice` of `linux-embedded-hal`.
+//!
+//! The registers are transferred as their raw representation, so they must set the `raw` attribute of the derive.
+//! The address of the register is sent first, followed by the bytes of the register, both in the order of [`RawBytes`].
+
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    i2c::I2c,
+    spi::{Operation, SpiDevice},
+};
+
+use crate::{
+    BusInterface, Device, RawAccessError, RawBytes, RawRegister, RawRegisterAccess,
+    RegisterInterface,
+};
+
+/// Maximum size of a transfer, the address and the register included
+pub const MAX_TRANSFER: usize = 32;
+
+/// Fails the compilation if the address and the register do not fit in a transfer
+struct Transfer<A, Raw>(PhantomData<(A, Raw)>);
+
+impl<A, Raw> Transfer<A, Raw>
+where
+    A: RawBytes,
+    Raw: RawBytes,
+{
+    const SIZE: usize = {
+        assert!(
+            A::SIZE + Raw::SIZE <= MAX_TRANSFER,
+            "the register does not fit in a transfer"
+        );
+        A::SIZE + Raw::SIZE
+    };
+
+    /// The number of values of a FIFO read in one transfer
+    const FIFO_CHUNK: usize = {
+        assert!(
+            A::SIZE + Raw::SIZE <= MAX_TRANSFER,
+            "the register does not fit in a transfer"
+        );
+        MAX_TRANSFER / Raw::SIZE
+    };
+}
+
+/// Writes the address of a raw access in big endian, fails if it does not fit in the bytes of `buf`
+fn raw_address<E>(address: u64, buf: &mut [u8]) -> Result<(), RawAccessError<E>> {
+    let bytes = address.to_be_bytes();
+    let (high, low) = bytes.split_at(bytes.len() - buf.len());
+    if high.iter().any(|&byte| byte != 0) {
+        return Err(RawAccessError::UnknownAddress(address));
+    }
+    buf.copy_from_slice(low);
+    Ok(())
+}
+
+/// Checks the address and the bytes of a raw write fit in a transfer
+fn raw_write_size<E>(address_size: usize, data: &[u8]) -> Result<(), RawAccessError<E>> {
+    if address_size + data.len() > MAX_TRANSFER {
+        return Err(RawAccessError::Size {
+            expected: MAX_TRANSFER - address_size,
+            actual: data.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Decodes the values of a FIFO from their bytes
+fn decode_fifo<R>(values: &mut [R], bytes: &[u8])
+where
+    R: RawRegister + From<R::Raw>,
+    R::Raw: RawBytes,
+{
+    for (value, bytes) in values.iter_mut().zip(bytes.chunks_exact(R::Raw::SIZE)) {
+        *value = R::Raw::from_bytes(bytes).into();
+    }
+}
+
+/// I2C bus implementing [`BusInterface`], the devices are selected by their 7 bits address.
+/// Use a [`Device`](crate::Device) to access the registers of a device on the bus, e.g. `Device::new(I2cBus::new(i2c), 0x48)`.
+///
+/// The register is read with a write of its address followed by a repeated start,
+/// and written in a single write of its address and its bytes.
+/// A FIFO is read the same way, with one read of all its values, split in transfers of up to [`MAX_TRANSFER`] bytes.
+///
+/// A [`Device`] on the bus also implements [`RawRegisterAccess`], for the tools where the registers are only known at runtime.
+#[derive(Debug)]
+pub struct I2cBus<I2C> {
+    i2c: I2C,
+    address_size: usize,
+}
+
+impl<I2C> I2cBus<I2C> {
+    /// Creates the bus from the I2C peripheral, with the addresses of the raw accesses sent on 1 byte
+    pub const fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            address_size: 1,
+        }
+    }
+
+    /// Sets the number of bytes of the addresses of the raw accesses, from 1 to 8.
+    /// The typed accesses send the address of the register on the size of its type.
+    pub const fn with_address_size(mut self, size: usize) -> Self {
+        assert!(
+            size >= 1 && size <= 8,
+            "the address size must be from 1 to 8 bytes"
+        );
+        self.address_size = size;
+        self
+    }
+
+    /// Returns a reference to the I2C peripheral
+    pub const fn inner(&self) -> &I2C {
+        &self.i2c
+    }
+
+    /// Returns a mutable reference to the I2C peripheral
+    pub fn inner_mut(&mut self) -> &mut I2C {
+        &mut self.i2c
+    }
+
+    /// Returns the I2C peripheral
+    pub fn into_inner(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C, R, A> BusInterface<R, A> for I2cBus<I2C>
+where
+    R: RawRegister<Address = A> + Clone + From<R::Raw>,
+    R::Raw: RawBytes + From<R>,
+    A: RawBytes,
+    I2C: I2c,
+{
+    type DeviceAddress = u8;
+    type Error = I2C::Error;
+
+    fn read_register(&mut self, device: &Self::DeviceAddress) -> Result<R, Self::Error> {
+        let mut buf = [0; MAX_TRANSFER];
+        let (address, bytes) = buf[..Transfer::<A, R::Raw>::SIZE].split_at_mut(A::SIZE);
+        R::ADDRESS.to_bytes(address);
+        self.i2c.write_read(*device, address, bytes)?;
+        Ok(R::Raw::from_bytes(bytes).into())
+    }
+
+    fn write_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        register: &R,
+    ) -> Result<(), Self::Error> {
+        let mut buf = [0; MAX_TRANSFER];
+        let buf = &mut buf[..Transfer::<A, R::Raw>::SIZE];
+        let (address, bytes) = buf.split_at_mut(A::SIZE);
+        R::ADDRESS.to_bytes(address);
+        R::Raw::from(register.clone()).to_bytes(bytes);
+        self.i2c.write(*device, buf)
+    }
+
+    fn read_fifo_register(
+        &mut self,
+        device: &Self::DeviceAddress,
+        values: &mut [R],
+    ) -> Result<(), Self::Error> {
+        let mut address = [0; MAX_TRANSFER];
+        let address = &mut address[..A::SIZE];
+        R::ADDRESS.to_bytes(address);
+        let mut buf = [0; MAX_TRANSFER];
+        for values in values.chunks_mut(Transfer::<A, R::Raw>::FIFO_CHUNK) {
+            let bytes = &mut buf[..values.len() * R::Raw::SIZE];
+            self.i2c.write_read(*device, address, bytes)?;
+            decode_fifo(values, bytes);
+        }
+        Ok(())
+    }
+}
+
+impl<I2C> RawRegisterAccess for Device<I2cBus<I2C>, u8>
+where
+    I2C: I2c,
+{
+    type Error = RawAccessError<I2C::Error>;
+
+    fn read_raw(&mut self, address: u64, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let (bus, device) = self.parts_mut();
+        let mut raw = [0; 8];
+        let raw = &mut raw[..bus.address_size];
+        raw_address(address, raw)?;
+        bus.i2c
+            .write_read(*device, raw, buf)
+            .map_err(RawAccessError::Interface)?;
+        Ok(buf.len())
+    }
+
+    fn write_raw(&mut self, address: u64, data: &[u8]) -> Result<(), Self::Error> {
+        let (bus, device) = self.parts_mut();
+        raw_write_size(bus.address_size, data)?;
+        let mut buf = [0; MAX_TRANSFER];
+        let buf = &mut buf[..bus.address_size + data.len()];
+        let (raw, bytes) = buf.split_at_mut(bus.address_size);
+        raw_address(address, raw)?;
+        bytes.copy_from_slice(data);
+        bus.i2c
+            .write(*device, buf)
+            .map_err(RawAccessError::Interface)
+    }
+}
+
+/// SPI device implementing [`RegisterInterface`], the chip select is handled by the [`SpiDevice`] for each transaction.
+///
+/// The register is read in a single transfer of its address followed by its bytes, and written the same way.
+/// A FIFO is read in one transaction of its address followed by the reads of all its values.
+/// The first byte of the address is combined with a flag indicating the direction,
+/// by default the most significant bit is set for the reads and nothing is set for the writes.
+///
+/// The interface also implements [`RawRegisterAccess`], for the tools where the registers are only known at runtime.
+#[derive(Debug)]
+pub struct SpiInterface<SPI> {
+    spi: SPI,
+    read_flag: u8,
+    write_flag: u8,
+    address_size: usize,
+}
+
+impl<SPI> SpiInterface<SPI> {
+    /// Creates the interface from the SPI peripheral, with the most significant bit of the address set for the reads
+    /// and the addresses of the raw accesses sent on 1 byte
+    pub const fn new(spi: SPI) -> Self {
+        Self {
+            spi,
+            read_flag: 0x80,
+            write_flag: 0x00,
+            address_size: 1,
+        }
+    }
+
+    /// Sets the number of bytes of the addresses of the raw accesses, from 1 to 8.
+    /// The typed accesses send the address of the register on the size of its type.
+    pub const fn with_address_size(mut self, size: usize) -> Self {
+        assert!(
+            size >= 1 && size <= 8,
+            "the address size must be from 1 to 8 bytes"
+        );
+        self.address_size = size;
+        self
+    }
+
+    /// Sets the flags combined with the first byte of the address for the reads and the writes
+    pub const fn with_flags(mut self, read: u8, write: u8) -> Self {
+        self.read_flag = read;
+        self.write_flag = write;
+        self
+    }
+
+    /// Writes the address of the register `R` in `buf`, with the flag combined to its first byte
+    fn address<R, A>(&self, buf: &mut [u8], flag: u8)
+    where
+        R: RawRegister<Address = A>,
+        A: RawBytes,
+    {
+        R::ADDRESS.to_bytes(&mut buf[..A::SIZE]);
+        buf[0] |= flag;
+    }
+
+    /// Returns a reference to the SPI peripheral
+    pub const fn inner(&self) -> &SPI {
+        &self.spi
+    }
+
+    /// Returns a mutable reference to the SPI peripheral
+    pub fn inner_mut(&mut self) -> &mut SPI {
+        &mut self.spi
+    }
+
+    /// Returns the SPI peripheral
+    pub fn into_inner(self) -> SPI {
+        self.spi
+    }
+}
+
+impl<SPI, R, A> RegisterInterface<R, A> for SpiInterface<SPI>
+where
+    R: RawRegister<Address = A> + Clone + From<R::Raw>,
+    R::Raw: RawBytes + From<R>,
+    A: RawBytes,
+    SPI: SpiDevice,
+{
+    type Error = SPI::Error;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let mut buf = [0; MAX_TRANSFER];
+        let buf = &mut buf[..Transfer::<A, R::Raw>::SIZE];
+        self.address::<R, A>(buf, self.read_flag);
+        self.spi.transfer_in_place(buf)?;
+        Ok(R::Raw::from_bytes(&buf[A::SIZE..]).into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let mut buf = [0; MAX_TRANSFER];
+        let buf = &mut buf[..Transfer::<A, R::Raw>::SIZE];
+        self.address::<R, A>(buf, self.write_flag);
+        R::Raw::from(register.clone()).to_bytes(&mut buf[A::SIZE..]);
+        self.spi.write(buf)
+    }
+
+    fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        let mut address = [0; MAX_TRANSFER];
+        let address = &mut address[..A::SIZE];
+        self.address::<R, A>(address, self.read_flag);
+        let mut buf = [0; MAX_TRANSFER];
+        for values in values.chunks_mut(Transfer::<A, R::Raw>::FIFO_CHUNK) {
+            let bytes = &mut buf[..values.len() * R::Raw::SIZE];
+            self.spi
+                .transaction(&mut [Operation::Write(address), Operation::Read(bytes)])?;
+            decode_fifo(values, bytes);
+        }
+        Ok(())
+    }
+}
+
+impl<SPI> RawRegisterAccess for SpiInterface<SPI>
+where
+    SPI: SpiDevice,
+{
+    type Error = RawAccessError<SPI::Error>;
+
+    fn read_raw(&mut self, address: u64, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut raw = [0; 8];
+        let raw = &mut raw[..self.address_size];
+        raw_address(address, raw)?;
+        raw[0] |= self.read_flag;
+        self.spi
+            .transaction(&mut [Operation::Write(raw), Operation::Read(buf)])
+            .map_err(RawAccessError::Interface)?;
+        Ok(buf.len())
+    }
+
+    fn write_raw(&mut self, address: u64, data: &[u8]) -> Result<(), Self::Error> {
+        raw_write_size(self.address_size, data)?;
+        let mut buf = [0; MAX_TRANSFER];
+        let buf = &mut buf[..self.address_size + data.len()];
+        let (raw, bytes) = buf.split_at_mut(self.address_size);
+        raw_address(address, raw)?;
+        raw[0] |= self.write_flag;
+        bytes.copy_from_slice(data);
+        self.spi.write(buf).map_err(RawAccessError::Interface)
+    }
+}
@@ -2,15 +2,211 @@
 #![no_std]
 #![deny(unsafe_code, missing_docs)]
 
+use core::ops::{BitAnd, BitOr};
+
 pub use device_register_macro::*;
+#[cfg(feature = "serde")]
+pub use serde;
+
+mod context;
+mod decode;
+mod device;
+pub mod export;
+mod field;
+mod fifo;
+#[cfg(feature = "embedded-hal")]
+pub mod hal;
+mod map;
+mod paged;
+mod protection;
+mod raw;
+mod retry;
+pub use context::{AccessError, Context, Operation};
+pub use decode::{RegisterError, TryFromRaw};
+pub use device::{BusInterface, Device};
+pub use field::{FieldEnum, InvalidValue};
+pub use fifo::{FifoRegister, ReadFifo};
+pub use map::{
+    dump, restore, Decoded, Difference, Entry, FieldChange, MapAccess, RegisterInfo, RegisterMap,
+    Snapshot,
+};
+pub use paged::Paged;
+pub use protection::{Key, ProtectedRegister, Unlock, Unprotected};
+pub use raw::{RawAccessError, RawBytes, RawMap, RawRegister, RawRegisterAccess};
+pub use retry::{Backoff, RetryInterface, RetryPolicy};
+
+/// Set of permissions of a register, as a bitflag.
+/// Allows to know at runtime if a register can be read, written or edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Permissions(u8);
+
+impl Permissions {
+    /// No permission
+    pub const NONE: Self = Self(0);
+
+    /// Permission to read the register
+    pub const READ: Self = Self(1 << 0);
+
+    /// Permission to write the register
+    pub const WRITE: Self = Self(1 << 1);
+
+    /// Permission to read-edit-write the register
+    pub const EDIT: Self = Self(1 << 2);
+
+    /// All the permissions
+    pub const ALL: Self = Self(Self::READ.0 | Self::WRITE.0 | Self::EDIT.0);
+
+    /// Returns the raw bits of the permissions
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Creates the permissions from raw bits, unknown bits are dropped
+    pub const fn from_bits_truncate(bits: u8) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+
+    /// Returns the union of both permissions
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Returns the intersection of both permissions
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// Returns true if all the permissions of `other` are contained in `self`
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if no permission is set
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if the register can be read
+    pub const fn is_readable(self) -> bool {
+        self.contains(Self::READ)
+    }
+
+    /// Returns true if the register can be written
+    pub const fn is_writable(self) -> bool {
+        self.contains(Self::WRITE)
+    }
+
+    /// Returns true if the register can be edited
+    pub const fn is_editable(self) -> bool {
+        self.contains(Self::EDIT)
+    }
+}
+
+impl BitOr for Permissions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for Permissions {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
 
 /// Trait of a register containing an address
 pub trait Register {
     /// Type of the adress, can be used to constrain the registers accepted
     type Address;
 
+    /// The protection of the register, either [`Unprotected`] or a [`Key`] written before each write.
+    /// The registers implemented manually are usually [`Unprotected`]
+    type Protection;
+
     /// The address of the register
     const ADDRESS: Self::Address;
+
+    /// The permissions of the register, matches the Read/Write/Edit traits implemented.
+    /// Usefull for generic tooling that needs to guard accesses at runtime.
+    /// Defaults to no permissions for the registers implemented manually
+    const PERMISSIONS: Permissions = Permissions::NONE;
+
+    /// The name of the register, empty by default for the registers implemented manually
+    const NAME: &'static str = "";
+
+    /// If reading the register modifies the state of the device, e.g. clear on read flags.
+    /// Those registers are skipped by tools that read the whole device
+    const READ_SIDE_EFFECTS: bool = false;
+
+    /// The description of the register, taken from its documentation
+    const DESCRIPTION: &'static str = "";
+
+    /// The value of the register after a reset of the device, if known
+    const RESET: Option<u64> = None;
+
+    /// The fields of the register, ordered by offset
+    const FIELDS: &'static [FieldInfo] = &[];
+
+    /// The page of the register on devices where the same address maps to different registers depending on the selected page.
+    /// `None` if the register is accessible from any page, see [`Paged`]
+    const PAGE: Option<u8> = None;
+
+    /// Decodes the register read by the interface, called by the read and edit accesses before returning it.
+    /// Accepts every value by default, so the registers implemented manually are infallible,
+    /// the derive rejects the invalid encodings with [`TryFromRaw`]
+    fn decode(register: Self) -> Result<Self, InvalidValue>
+    where
+        Self: Sized,
+    {
+        Ok(register)
+    }
+}
+
+/// Static description of a field of a register, a range of bits with its own meaning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// The name of the field
+    pub name: &'static str,
+
+    /// The description of the field
+    pub description: &'static str,
+
+    /// The offset of the least significant bit of the field
+    pub offset: u32,
+
+    /// The number of bits of the field
+    pub width: u32,
+
+    /// The permissions of the field, usually the same as the register
+    pub permissions: Permissions,
+}
+
+impl FieldInfo {
+    /// Returns the mask of the field, already shifted at its offset
+    pub const fn mask(&self) -> u64 {
+        let mask = if self.width >= 64 {
+            u64::MAX
+        } else {
+            (1 << self.width) - 1
+        };
+        // The bits beyond the 64 bits of the raw value are dropped
+        match mask.checked_shl(self.offset) {
+            Some(mask) => mask,
+            None => 0,
+        }
+    }
+
+    /// Extracts the value of the field from the raw value of the register
+    pub const fn extract(&self, raw: u64) -> u64 {
+        match (raw & self.mask()).checked_shr(self.offset) {
+            Some(value) => value,
+            None => 0,
+        }
+    }
 }
 
 /// Trait of a read only  register
@@ -38,6 +234,15 @@ where
 
     /// Writes a register to the device
     fn write_register(&mut self, register: &R) -> Result<(), Self::Error>;
+
+    /// Reads the register as many times as the length of `values`, used by the FIFO registers.
+    /// Reads each value on its own by default, implement it as one bulk transfer if the device doesn't auto increment the address
+    fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        for value in values {
+            *value = self.read_register()?;
+        }
+        Ok(())
+    }
 }
 
 /// Trait to safely read a register. Only a readable register can be read.
@@ -86,21 +291,24 @@ where
     R: ReadableRegister<Address = A>,
     I: RegisterInterface<R, A>,
 {
-    type Error = I::Error;
+    type Error = RegisterError<I::Error>;
 
     fn read(&mut self) -> Result<R, Self::Error> {
-        self.read_register()
+        let val = self.read_register().map_err(RegisterError::Bus)?;
+        Ok(R::decode(val)?)
     }
 }
 
 impl<I, R, A> WriteRegister<R, A> for I
 where
     R: WritableRegister<Address = A>,
+    R::Protection: Unlock<I, I::Error>,
     I: RegisterInterface<R, A>,
 {
     type Error = I::Error;
 
     fn write(&mut self, register: R) -> Result<(), Self::Error> {
+        R::Protection::unlock(self)?;
         self.write_register(&register)
     }
 }
@@ -108,16 +316,19 @@ where
 impl<I, R, A> EditRegister<R, A> for I
 where
     R: EditableRegister<Address = A>,
+    R::Protection: Unlock<I, I::Error>,
     I: RegisterInterface<R, A>,
 {
-    type Error = I::Error;
+    type Error = RegisterError<I::Error>;
 
     fn edit<F>(&mut self, f: F) -> Result<(), Self::Error>
     where
         for<'w> F: FnOnce(&'w mut R),
     {
-        let mut val = self.read_register()?;
+        let val = self.read_register().map_err(RegisterError::Bus)?;
+        let mut val = R::decode(val)?;
         f(&mut val);
-        self.write_register(&val)
+        R::Protection::unlock(self).map_err(RegisterError::Bus)?;
+        self.write_register(&val).map_err(RegisterError::Bus)
     }
 }
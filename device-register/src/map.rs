@@ -0,0 +1,568 @@
+//! Description of all the registers of a device and the tools built on top of it
+
+use core::{fmt, ops::BitXor};
+
+use crate::{FieldInfo, Permissions, Register};
+
+/// Static description of a register that is part of a [`RegisterMap`]
+pub struct RegisterInfo<A, Raw> {
+    /// The name of the register
+    pub name: &'static str,
+
+    /// The address of the register
+    pub address: A,
+
+    /// The permissions of the register
+    pub permissions: Permissions,
+
+    /// If reading the register modifies the state of the device
+    pub read_side_effects: bool,
+
+    /// The description of the register
+    pub description: &'static str,
+
+    /// The value of the register after a reset, if known
+    pub reset: Option<u64>,
+
+    /// The fields of the register, ordered by offset
+    pub fields: &'static [FieldInfo],
+
+    /// The page of the register, if the device has paged registers
+    pub page: Option<u8>,
+
+    decode: fn(&Raw, &mut fmt::Formatter<'_>) -> fmt::Result,
+}
+
+impl<A, Raw> RegisterInfo<A, Raw> {
+    /// Creates the description of the register `R`
+    pub const fn new<R>() -> Self
+    where
+        R: Register<Address = A> + From<Raw> + fmt::Debug,
+        Raw: Clone,
+    {
+        Self {
+            name: R::NAME,
+            address: R::ADDRESS,
+            permissions: R::PERMISSIONS,
+            read_side_effects: R::READ_SIDE_EFFECTS,
+            description: R::DESCRIPTION,
+            reset: R::RESET,
+            fields: R::FIELDS,
+            page: R::PAGE,
+            decode: decode::<R, Raw>,
+        }
+    }
+
+    /// Returns true if the register can be read without altering the device
+    pub const fn is_dumpable(&self) -> bool {
+        self.permissions.is_readable() && !self.read_side_effects
+    }
+
+    /// Decodes a raw value as the register, the result can be formatted to display the fields
+    pub fn decode<'a>(&'a self, raw: &'a Raw) -> Decoded<'a, Raw> {
+        Decoded {
+            raw,
+            decode: self.decode,
+        }
+    }
+}
+
+impl<A, Raw> fmt::Debug for RegisterInfo<A, Raw>
+where
+    A: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegisterInfo")
+            .field("name", &self.name)
+            .field("address", &self.address)
+            .field("permissions", &self.permissions)
+            .field("read_side_effects", &self.read_side_effects)
+            .field("description", &self.description)
+            .field("reset", &self.reset)
+            .field("fields", &self.fields)
+            .field("page", &self.page)
+            .finish()
+    }
+}
+
+fn decode<R, Raw>(raw: &Raw, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+    R: From<Raw> + fmt::Debug,
+    Raw: Clone,
+{
+    fmt::Debug::fmt(&R::from(raw.clone()), f)
+}
+
+/// A raw value decoded as its register, formats the register using its `Debug` implementation
+pub struct Decoded<'a, Raw> {
+    raw: &'a Raw,
+    decode: fn(&Raw, &mut fmt::Formatter<'_>) -> fmt::Result,
+}
+
+impl<Raw> fmt::Debug for Decoded<'_, Raw> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.decode)(self.raw, f)
+    }
+}
+
+/// Trait of a register map, the list of all the registers of a device.
+/// Should be implemented using the [`RegisterMap`](device_register_macro::RegisterMap) derive.
+pub trait RegisterMap: Sized {
+    /// Type of the address of the registers
+    type Address: 'static;
+
+    /// Raw representation shared by all the registers of the map
+    type Raw: 'static;
+
+    /// Storage of an optional raw value per register, usually `[Option<Self::Raw>; N]`
+    type Values: AsRef<[Option<Self::Raw>]> + AsMut<[Option<Self::Raw>]>;
+
+    /// The name of the map
+    const NAME: &'static str;
+
+    /// The description of the registers of the map, in declaration order
+    const REGISTERS: &'static [RegisterInfo<Self::Address, Self::Raw>];
+
+    /// Storage with no value for every register
+    const EMPTY: Self::Values;
+
+    /// The indices in [`RegisterMap::REGISTERS`] of the registers that [`restore`] writes last, in this order.
+    /// The other registers are written before them in declaration order, which is the whole order when empty, the default
+    const RESTORE_ORDER: &'static [usize] = &[];
+}
+
+/// Trait that define how to access the registers of a map through the interface `I`, using their raw representation.
+/// Implemented by the [`RegisterMap`](device_register_macro::RegisterMap) derive for all the [`RegisterInterface`](crate::RegisterInterface) that support every register of the map.
+/// Note that those functions are not bound by Read/Write/Edit permission, but the writes perform the unlock sequence of the protected registers.
+pub trait MapAccess<I>: RegisterMap {
+    /// Error type returned by the interface
+    type Error;
+
+    /// Reads the register at `index` in [`RegisterMap::REGISTERS`].
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds
+    fn read_raw(iface: &mut I, index: usize) -> Result<Self::Raw, Self::Error>;
+
+    /// Writes the register at `index` in [`RegisterMap::REGISTERS`], after unlocking it if it is protected.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds
+    fn write_raw(iface: &mut I, index: usize, raw: Self::Raw) -> Result<(), Self::Error>;
+}
+
+/// The raw values of the registers of a map at a given time
+pub struct Snapshot<M>
+where
+    M: RegisterMap,
+{
+    values: M::Values,
+}
+
+impl<M> Snapshot<M>
+where
+    M: RegisterMap,
+{
+    /// Creates a snapshot from the raw values, in the order of [`RegisterMap::REGISTERS`]
+    pub fn from_values(values: M::Values) -> Self {
+        Self { values }
+    }
+
+    /// Returns the raw values, in the order of [`RegisterMap::REGISTERS`].
+    /// Registers that were not read have no value
+    pub fn values(&self) -> &[Option<M::Raw>] {
+        self.values.as_ref()
+    }
+
+    /// Returns the raw value of a register by name, if it was read
+    pub fn get(&self, name: &str) -> Option<&M::Raw> {
+        self.entries()
+            .find(|entry| entry.info.name == name)
+            .and_then(|entry| entry.raw)
+    }
+
+    /// Iterates over the registers of the snapshot
+    pub fn entries(&self) -> impl Iterator<Item = Entry<'_, M::Address, M::Raw>> {
+        M::REGISTERS
+            .iter()
+            .zip(self.values())
+            .map(|(info, raw)| Entry {
+                info,
+                raw: raw.as_ref(),
+            })
+    }
+}
+
+impl<M> Snapshot<M>
+where
+    M: RegisterMap,
+    M::Raw: PartialEq,
+{
+    /// Iterates over the registers that differ between `self` and `other`.
+    /// A register read in only one of the snapshots is considered as changed
+    pub fn diff<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> impl Iterator<Item = Difference<'a, M::Address, M::Raw>> {
+        M::REGISTERS
+            .iter()
+            .zip(self.values().iter().zip(other.values()))
+            .filter(|(_, (before, after))| before != after)
+            .map(|(info, (before, after))| Difference {
+                info,
+                before: before.as_ref(),
+                after: after.as_ref(),
+            })
+    }
+}
+
+impl<M> Clone for Snapshot<M>
+where
+    M: RegisterMap,
+    M::Values: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<M> PartialEq for Snapshot<M>
+where
+    M: RegisterMap,
+    M::Raw: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.values() == other.values()
+    }
+}
+
+impl<M> fmt::Debug for Snapshot<M>
+where
+    M: RegisterMap,
+    M::Address: fmt::Debug,
+    M::Raw: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.entries()).finish()
+    }
+}
+
+impl<M> fmt::Display for Snapshot<M>
+where
+    M: RegisterMap,
+    M::Address: fmt::Debug,
+    M::Raw: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in self.entries() {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// A register of a [`Snapshot`]
+pub struct Entry<'a, A, Raw> {
+    /// The description of the register
+    pub info: &'a RegisterInfo<A, Raw>,
+
+    /// The raw value of the register, `None` if it was not read
+    pub raw: Option<&'a Raw>,
+}
+
+impl<A, Raw> Entry<'_, A, Raw> {
+    /// Decodes the value of the register, if it was read
+    pub fn decoded(&self) -> Option<Decoded<'_, Raw>> {
+        self.raw.map(|raw| self.info.decode(raw))
+    }
+}
+
+impl<A, Raw> fmt::Debug for Entry<'_, A, Raw>
+where
+    A: fmt::Debug,
+    Raw: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entry")
+            .field("name", &self.info.name)
+            .field("address", &self.info.address)
+            .field("raw", &self.raw)
+            .field("decoded", &self.decoded())
+            .finish()
+    }
+}
+
+impl<A, Raw> fmt::Display for Entry<'_, A, Raw>
+where
+    A: fmt::Debug,
+    Raw: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} @ {:?}: ", self.info.name, self.info.address)?;
+        match (self.raw, self.decoded()) {
+            (Some(raw), Some(decoded)) => write!(f, "{:#x?} {:?}", raw, decoded),
+            _ => write!(f, "<skipped>"),
+        }
+    }
+}
+
+/// A register that changed between two [`Snapshot`]
+pub struct Difference<'a, A, Raw> {
+    /// The description of the register
+    pub info: &'a RegisterInfo<A, Raw>,
+
+    /// The raw value in the first snapshot, `None` if it was not read
+    pub before: Option<&'a Raw>,
+
+    /// The raw value in the second snapshot, `None` if it was not read
+    pub after: Option<&'a Raw>,
+}
+
+impl<A, Raw> Difference<'_, A, Raw> {
+    /// Returns the bits that changed, if the register was read in both snapshots
+    pub fn changed_bits(&self) -> Option<Raw>
+    where
+        Raw: Clone + BitXor<Output = Raw>,
+    {
+        match (self.before, self.after) {
+            (Some(before), Some(after)) => Some(before.clone() ^ after.clone()),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the fields that changed, if the register was read in both snapshots.
+    /// Registers without [`Register::FIELDS`] have no field, use [`Difference::changed_bits`] instead
+    pub fn changed_fields(&self) -> impl Iterator<Item = FieldChange> + '_
+    where
+        Raw: Clone + Into<u64>,
+    {
+        let values = match (self.before, self.after) {
+            (Some(before), Some(after)) => Some((before.clone().into(), after.clone().into())),
+            _ => None,
+        };
+        let fields = values.map_or(&[][..], |_| self.info.fields);
+        fields.iter().filter_map(move |field| {
+            let (before, after) = values?;
+            let change = FieldChange {
+                field,
+                before: field.extract(before),
+                after: field.extract(after),
+            };
+            (change.before != change.after).then_some(change)
+        })
+    }
+}
+
+/// A field that changed between two [`Snapshot`], see [`Difference::changed_fields`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldChange {
+    /// The description of the field
+    pub field: &'static FieldInfo,
+
+    /// The value of the field in the first snapshot
+    pub before: u64,
+
+    /// The value of the field in the second snapshot
+    pub after: u64,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:#x} -> {:#x}",
+            self.field.name, self.before, self.after
+        )
+    }
+}
+
+impl<A, Raw> fmt::Debug for Difference<'_, A, Raw>
+where
+    A: fmt::Debug,
+    Raw: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Difference")
+            .field("name", &self.info.name)
+            .field("address", &self.info.address)
+            .field("before", &self.before.map(|raw| self.info.decode(raw)))
+            .field("after", &self.after.map(|raw| self.info.decode(raw)))
+            .finish()
+    }
+}
+
+impl<A, Raw> fmt::Display for Difference<'_, A, Raw>
+where
+    A: fmt::Debug,
+    Raw: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} @ {:?}: ", self.info.name, self.info.address)?;
+        match self.before {
+            Some(raw) => write!(f, "{:?}", self.info.decode(raw))?,
+            None => write!(f, "<skipped>")?,
+        }
+        write!(f, " -> ")?;
+        match self.after {
+            Some(raw) => write!(f, "{:?}", self.info.decode(raw)),
+            None => write!(f, "<skipped>"),
+        }
+    }
+}
+
+/// Reads all the readable registers of the map and returns a snapshot of their values.
+/// Write only registers and registers with side effects on read are skipped.
+pub fn dump<M, I>(iface: &mut I) -> Result<Snapshot<M>, M::Error>
+where
+    M: MapAccess<I>,
+{
+    let mut values = M::EMPTY;
+    for (index, (info, value)) in M::REGISTERS
+        .iter()
+        .zip(values.as_mut().iter_mut())
+        .enumerate()
+    {
+        if info.is_dumpable() {
+            *value = Some(M::read_raw(iface, index)?);
+        }
+    }
+    Ok(Snapshot { values })
+}
+
+/// Writes back all the writable and editable registers of the snapshot.
+/// The registers are written in declaration order, except the ones of [`RegisterMap::RESTORE_ORDER`] that are written last in their own order,
+/// e.g. the enable register after the configuration it depends on.
+/// Each register is written once with its whole value in the snapshot, the editable ones included since the snapshot holds the reserved bits read from the device.
+/// Registers that were not read are skipped, and the protected registers are unlocked before being written.
+pub fn restore<M, I>(iface: &mut I, snapshot: &Snapshot<M>) -> Result<(), M::Error>
+where
+    M: MapAccess<I>,
+    M::Raw: Clone,
+{
+    let values = snapshot.values();
+    let first = (0..values.len()).filter(|index| !M::RESTORE_ORDER.contains(index));
+    for index in first.chain(M::RESTORE_ORDER.iter().copied()) {
+        let permissions = M::REGISTERS[index].permissions;
+        let restorable = permissions.is_writable() || permissions.is_editable();
+        if let (true, Some(raw)) = (restorable, &values[index]) {
+            M::write_raw(iface, index, raw.clone())?;
+        }
+    }
+    Ok(())
+}
+
+/// Serialization of the snapshots, as a map of the register names to their raw values
+#[cfg(feature = "serde")]
+mod serialization {
+    use core::{fmt, marker::PhantomData};
+
+    use serde::{
+        de::{self, DeserializeSeed, Visitor},
+        ser::SerializeMap,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    use super::{RegisterMap, Snapshot};
+
+    impl<M> Serialize for Snapshot<M>
+    where
+        M: RegisterMap,
+        M::Raw: Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let len = self.values().iter().filter(|raw| raw.is_some()).count();
+            let mut map = serializer.serialize_map(Some(len))?;
+            for entry in self.entries() {
+                if let Some(raw) = entry.raw {
+                    map.serialize_entry(entry.info.name, raw)?;
+                }
+            }
+            map.end()
+        }
+    }
+
+    impl<'de, M> Deserialize<'de> for Snapshot<M>
+    where
+        M: RegisterMap,
+        M::Raw: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_map(SnapshotVisitor(PhantomData))
+        }
+    }
+
+    struct SnapshotVisitor<M>(PhantomData<M>);
+
+    impl<'de, M> Visitor<'de> for SnapshotVisitor<M>
+    where
+        M: RegisterMap,
+        M::Raw: Deserialize<'de>,
+    {
+        type Value = Snapshot<M>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a map of register names to raw values")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::MapAccess<'de>,
+        {
+            let mut values = M::EMPTY;
+            while let Some(index) = map.next_key_seed(RegisterIndex::<M>(PhantomData))? {
+                let value = &mut values.as_mut()[index];
+                if value.is_some() {
+                    return Err(de::Error::duplicate_field(M::REGISTERS[index].name));
+                }
+                *value = Some(map.next_value()?);
+            }
+            Ok(Snapshot::from_values(values))
+        }
+    }
+
+    /// Deserializes a register name as its index in the map, without allocating
+    struct RegisterIndex<M>(PhantomData<M>);
+
+    impl<'de, M> DeserializeSeed<'de> for RegisterIndex<M>
+    where
+        M: RegisterMap,
+    {
+        type Value = usize;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_str(self)
+        }
+    }
+
+    impl<'de, M> Visitor<'de> for RegisterIndex<M>
+    where
+        M: RegisterMap,
+    {
+        type Value = usize;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "the name of a register of the map")
+        }
+
+        fn visit_str<E>(self, name: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            M::REGISTERS
+                .iter()
+                .position(|info| info.name == name)
+                .ok_or_else(|| E::custom(format_args!("unknown register `{}`", name)))
+        }
+    }
+}
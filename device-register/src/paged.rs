@@ -0,0 +1,117 @@
+//! Support of the devices where the same address maps to different registers depending on the selected page (or bank)
+
+use crate::{Register, RegisterInterface};
+
+/// Interface wrapper for devices with paged registers.
+/// Tracks the current page of the device and writes the page select register `S`
+/// before accessing a register declared on another page, e.g. `#[register(addr = "0x10", page = 2)]`.
+/// Registers without a page are accessible from any page and never change the current page.
+///
+/// The page select register must be writable through the interface and be created from the page number.
+/// Writing the page select register directly, or resetting the device, desyncs the tracked page,
+/// use [`Paged::invalidate`] so it is selected again on the next access.
+#[derive(Debug)]
+pub struct Paged<I, S> {
+    iface: I,
+    page: Option<u8>,
+    _select: core::marker::PhantomData<fn(S)>,
+}
+
+impl<I, S> Paged<I, S> {
+    /// Wraps the interface, the current page is unknown so it is selected on the first paged access
+    pub const fn new(iface: I) -> Self {
+        Self {
+            iface,
+            page: None,
+            _select: core::marker::PhantomData,
+        }
+    }
+
+    /// Wraps the interface of a device known to be on `page`, e.g. the page after a reset
+    pub const fn with_page(iface: I, page: u8) -> Self {
+        Self {
+            iface,
+            page: Some(page),
+            _select: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns the page currently selected on the device, if known
+    pub const fn page(&self) -> Option<u8> {
+        self.page
+    }
+
+    /// Sets the page known to be selected on the device, e.g. after selecting it with the inner interface
+    pub fn assume_page(&mut self, page: u8) {
+        self.page = Some(page);
+    }
+
+    /// Forgets the current page, it will be selected again on the next paged access
+    pub fn invalidate(&mut self) {
+        self.page = None;
+    }
+
+    /// Returns a reference to the inner interface
+    pub const fn inner(&self) -> &I {
+        &self.iface
+    }
+
+    /// Returns a mutable reference to the inner interface.
+    /// Use [`Paged::invalidate`] if the page is changed through it
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iface
+    }
+
+    /// Returns the inner interface
+    pub fn into_inner(self) -> I {
+        self.iface
+    }
+}
+
+impl<I, S> Paged<I, S>
+where
+    S: Register + From<u8>,
+    I: RegisterInterface<S, S::Address>,
+{
+    /// Selects the page on the device, even if it is already the current page
+    pub fn select(&mut self, page: u8) -> Result<(), I::Error> {
+        // The page is unknown if the write fails midway
+        self.page = None;
+        self.iface.write_register(&S::from(page))?;
+        self.page = Some(page);
+        Ok(())
+    }
+
+    /// Selects the page of the register `R` if it is on another page than the current one
+    fn select_for<R: Register>(&mut self) -> Result<(), I::Error> {
+        match R::PAGE {
+            Some(page) if self.page != Some(page) => self.select(page),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<I, S, R, A> RegisterInterface<R, A> for Paged<I, S>
+where
+    R: Register<Address = A>,
+    S: Register + From<u8>,
+    I: RegisterInterface<R, A>,
+    I: RegisterInterface<S, S::Address, Error = <I as RegisterInterface<R, A>>::Error>,
+{
+    type Error = <I as RegisterInterface<R, A>>::Error;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.select_for::<R>()?;
+        RegisterInterface::<R, A>::read_register(&mut self.iface)
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.select_for::<R>()?;
+        RegisterInterface::<R, A>::write_register(&mut self.iface, register)
+    }
+
+    fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.select_for::<R>()?;
+        RegisterInterface::<R, A>::read_fifo_register(&mut self.iface, values)
+    }
+}
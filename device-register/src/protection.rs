@@ -0,0 +1,46 @@
+//! Support of the registers that must be unlocked by writing a key to another register before each write
+
+use core::marker::PhantomData;
+
+use crate::{Register, RegisterInterface, WritableRegister};
+
+/// Protection of a register that can be written without unlocking it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Unprotected;
+
+/// Protection of the register `R`, the key of `R` is written to its unlock register before each write.
+/// Declared using `#[register(protected(by = UnlockRegister, key = 0xA5))]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Key<R>(PhantomData<R>);
+
+/// Trait of a register protected by a key, implemented by the `protected` attribute of the derives
+pub trait ProtectedRegister: Register {
+    /// The register the key is written to
+    type Unlock: WritableRegister;
+
+    /// Returns the value of the unlock register that unlocks this register
+    fn key() -> Self::Unlock;
+}
+
+/// Trait of the unlock sequence of a protection, performed through the interface `I` before writing a register.
+/// Used by [`WriteRegister`](crate::WriteRegister) and [`EditRegister`](crate::EditRegister) so the sequence can't be forgotten.
+pub trait Unlock<I, E> {
+    /// Performs the unlock sequence
+    fn unlock(iface: &mut I) -> Result<(), E>;
+}
+
+impl<I, E> Unlock<I, E> for Unprotected {
+    fn unlock(_iface: &mut I) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+impl<I, E, R> Unlock<I, E> for Key<R>
+where
+    R: ProtectedRegister,
+    I: RegisterInterface<R::Unlock, <R::Unlock as Register>::Address, Error = E>,
+{
+    fn unlock(iface: &mut I) -> Result<(), E> {
+        iface.write_register(&R::key())
+    }
+}
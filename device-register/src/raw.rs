@@ -0,0 +1,272 @@
+//! Untyped access to the registers by address, for tools where the register is only known at runtime, like a debug console
+
+use core::{fmt, marker::PhantomData};
+
+use crate::{export::NumericAddress, MapAccess, Register, RegisterInterface};
+
+/// Object safe access to the registers using their address and their raw bytes.
+/// Can be used as a `dyn RawRegisterAccess<Error = E>` when the register is chosen at runtime.
+/// Implemented for register maps by [`RawMap`].
+pub trait RawRegisterAccess {
+    /// Error type returned by the accesses
+    type Error;
+
+    /// Reads the register at `address` into `buf` and returns the number of bytes read
+    fn read_raw(&mut self, address: u64, buf: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Writes the bytes of the register at `address`, the size of `data` must match the size of the register
+    fn write_raw(&mut self, address: u64, data: &[u8]) -> Result<(), Self::Error>;
+
+    /// Edits the register at `address`, its bytes are read into `buf`, modified by `f` then written back.
+    /// Allows to edit the registers that can't be read or written on their own, like the edit only registers.
+    /// Reads then writes the register by default
+    fn edit_raw(
+        &mut self,
+        address: u64,
+        buf: &mut [u8],
+        f: &mut dyn FnMut(&mut [u8]),
+    ) -> Result<(), Self::Error> {
+        let len = self.read_raw(address, buf)?;
+        f(&mut buf[..len]);
+        self.write_raw(address, &buf[..len])
+    }
+}
+
+/// Conversion of the raw representation of the registers to bytes.
+/// The integers are in big endian, use a byte array as the raw representation for other orders
+pub trait RawBytes: Sized {
+    /// The number of bytes of the representation
+    const SIZE: usize;
+
+    /// Writes the bytes in `buf`, of length [`RawBytes::SIZE`]
+    fn to_bytes(&self, buf: &mut [u8]);
+
+    /// Reads the representation from `bytes`, of length [`RawBytes::SIZE`]
+    fn from_bytes(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_raw_bytes {
+    ($($ty:ty),*) => {
+        $(
+            impl RawBytes for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                fn to_bytes(&self, buf: &mut [u8]) {
+                    buf.copy_from_slice(&self.to_be_bytes());
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Self {
+                    let mut buf = [0; core::mem::size_of::<$ty>()];
+                    buf.copy_from_slice(bytes);
+                    <$ty>::from_be_bytes(buf)
+                }
+            }
+        )*
+    };
+}
+
+impl_raw_bytes!(u8, u16, u32, u64);
+
+impl<const N: usize> RawBytes for [u8; N] {
+    const SIZE: usize = N;
+
+    fn to_bytes(&self, buf: &mut [u8]) {
+        buf.copy_from_slice(self);
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let mut buf = [0; N];
+        buf.copy_from_slice(bytes);
+        buf
+    }
+}
+
+/// Register that has a raw representation, implemented by the derive when the `raw` attribute is set.
+/// Used by the interfaces that transfer the registers as bytes
+pub trait RawRegister: Register {
+    /// The raw representation of the register
+    type Raw;
+}
+
+/// Error of a raw access
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawAccessError<E> {
+    /// No register at this address
+    UnknownAddress(u64),
+
+    /// The register does not allow this access, e.g. writing a read only register
+    Denied(&'static str),
+
+    /// The size of the buffer does not match the size of the register
+    Size {
+        /// The size of the register
+        expected: usize,
+
+        /// The size of the buffer
+        actual: usize,
+    },
+
+    /// Error returned by the interface
+    Interface(E),
+}
+
+impl<E> fmt::Display for RawAccessError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawAccessError::UnknownAddress(address) => {
+                write!(f, "no register at address {:#x}", address)
+            }
+            RawAccessError::Denied(name) => write!(f, "access denied to register {}", name),
+            RawAccessError::Size { expected, actual } => write!(
+                f,
+                "register has {} bytes but the buffer has {} bytes",
+                expected, actual
+            ),
+            RawAccessError::Interface(err) => write!(f, "interface error: {}", err),
+        }
+    }
+}
+
+/// Bridge giving [`RawRegisterAccess`] to the interface `I` of the register map `M`.
+/// The addresses are looked up in the map, and the permissions of the registers are enforced.
+/// If multiple registers share an address, e.g. paged registers, the first one is used.
+/// The raw writes perform the unlock sequence of the protected registers like [`WriteRegister`](crate::WriteRegister),
+/// and the editable registers are edited with [`RawRegisterAccess::edit_raw`] like [`EditRegister`](crate::EditRegister).
+///
+/// Typed accesses are still available since the bridge forwards [`RegisterInterface`] to the inner interface.
+#[derive(Debug)]
+pub struct RawMap<M, I> {
+    iface: I,
+    _map: PhantomData<fn() -> M>,
+}
+
+impl<M, I> RawMap<M, I> {
+    /// Creates the bridge for the interface
+    pub const fn new(iface: I) -> Self {
+        Self {
+            iface,
+            _map: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the inner interface
+    pub const fn inner(&self) -> &I {
+        &self.iface
+    }
+
+    /// Returns a mutable reference to the inner interface
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iface
+    }
+
+    /// Returns the inner interface
+    pub fn into_inner(self) -> I {
+        self.iface
+    }
+}
+
+impl<M, I> RawMap<M, I>
+where
+    M: MapAccess<I>,
+    M::Address: NumericAddress,
+{
+    /// Returns the index of the register at `address` in the map
+    fn index(address: u64) -> Result<usize, RawAccessError<M::Error>> {
+        M::REGISTERS
+            .iter()
+            .position(|info| info.address.value() == address)
+            .ok_or(RawAccessError::UnknownAddress(address))
+    }
+}
+
+impl<M, I> RawRegisterAccess for RawMap<M, I>
+where
+    M: MapAccess<I>,
+    M::Address: NumericAddress,
+    M::Raw: RawBytes,
+{
+    type Error = RawAccessError<M::Error>;
+
+    fn read_raw(&mut self, address: u64, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let index = Self::index(address)?;
+        let info = &M::REGISTERS[index];
+        if !info.permissions.is_readable() {
+            return Err(RawAccessError::Denied(info.name));
+        }
+        let size = M::Raw::SIZE;
+        if buf.len() < size {
+            return Err(RawAccessError::Size {
+                expected: size,
+                actual: buf.len(),
+            });
+        }
+        let raw = M::read_raw(&mut self.iface, index).map_err(RawAccessError::Interface)?;
+        raw.to_bytes(&mut buf[..size]);
+        Ok(size)
+    }
+
+    fn write_raw(&mut self, address: u64, data: &[u8]) -> Result<(), Self::Error> {
+        let index = Self::index(address)?;
+        let info = &M::REGISTERS[index];
+        if !info.permissions.is_writable() {
+            return Err(RawAccessError::Denied(info.name));
+        }
+        if data.len() != M::Raw::SIZE {
+            return Err(RawAccessError::Size {
+                expected: M::Raw::SIZE,
+                actual: data.len(),
+            });
+        }
+        M::write_raw(&mut self.iface, index, M::Raw::from_bytes(data))
+            .map_err(RawAccessError::Interface)
+    }
+
+    fn edit_raw(
+        &mut self,
+        address: u64,
+        buf: &mut [u8],
+        f: &mut dyn FnMut(&mut [u8]),
+    ) -> Result<(), Self::Error> {
+        let index = Self::index(address)?;
+        let info = &M::REGISTERS[index];
+        if !info.permissions.is_editable() {
+            return Err(RawAccessError::Denied(info.name));
+        }
+        let size = M::Raw::SIZE;
+        if buf.len() < size {
+            return Err(RawAccessError::Size {
+                expected: size,
+                actual: buf.len(),
+            });
+        }
+        let buf = &mut buf[..size];
+        let raw = M::read_raw(&mut self.iface, index).map_err(RawAccessError::Interface)?;
+        raw.to_bytes(buf);
+        f(buf);
+        M::write_raw(&mut self.iface, index, M::Raw::from_bytes(buf))
+            .map_err(RawAccessError::Interface)
+    }
+}
+
+impl<M, I, R, A> RegisterInterface<R, A> for RawMap<M, I>
+where
+    R: Register<Address = A>,
+    I: RegisterInterface<R, A>,
+{
+    type Error = I::Error;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.iface.read_register()
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.iface.write_register(register)
+    }
+
+    fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.iface.read_fifo_register(values)
+    }
+}
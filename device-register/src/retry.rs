@@ -0,0 +1,196 @@
+//! Retry of the accesses that failed, e.g. on transient NACKs of a noisy bus
+
+use crate::{Register, RegisterInterface, Unlock};
+
+/// Policy deciding if a failed access is retried.
+/// Implemented for a `u32`, the number of retries of any error,
+/// for closures `FnMut(attempt, &error) -> bool` and for [`Backoff`].
+pub trait RetryPolicy<E> {
+    /// Called after each failed `attempt`, starting at 1.
+    /// Returns true to retry the access, after waiting the backoff if any
+    fn retry(&mut self, attempt: u32, error: &E) -> bool;
+}
+
+impl<E> RetryPolicy<E> for u32 {
+    fn retry(&mut self, attempt: u32, _error: &E) -> bool {
+        attempt <= *self
+    }
+}
+
+impl<E, F> RetryPolicy<E> for F
+where
+    F: FnMut(u32, &E) -> bool,
+{
+    fn retry(&mut self, attempt: u32, error: &E) -> bool {
+        self(attempt, error)
+    }
+}
+
+/// Policy retrying the errors accepted by a predicate, waiting an exponential backoff between the attempts.
+/// The first retry waits `backoff_us` microseconds, and the wait doubles after each attempt.
+/// The delay provider is a function waiting the given microseconds, e.g. `|us| delay.delay_us(us)`
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff<D, F> {
+    retries: u32,
+    backoff_us: u32,
+    delay: D,
+    predicate: F,
+}
+
+impl<D, F> Backoff<D, F> {
+    /// Creates the policy retrying up to `retries` times the errors for which `predicate` returns true
+    pub const fn new(retries: u32, backoff_us: u32, delay: D, predicate: F) -> Self {
+        Self {
+            retries,
+            backoff_us,
+            delay,
+            predicate,
+        }
+    }
+
+    /// Returns the maximum number of retries
+    pub const fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Returns the delay in microseconds before the retry of the failed `attempt`
+    pub const fn backoff_us(&self, attempt: u32) -> u32 {
+        let shift = if attempt > 32 {
+            31
+        } else {
+            attempt.saturating_sub(1)
+        };
+        self.backoff_us.saturating_mul(1 << shift)
+    }
+
+    /// Returns a mutable reference to the delay provider
+    pub fn delay_mut(&mut self) -> &mut D {
+        &mut self.delay
+    }
+
+    /// Returns true if the failed `attempt` must be retried, without waiting the backoff
+    pub fn should_retry<E>(&mut self, attempt: u32, error: &E) -> bool
+    where
+        F: FnMut(&E) -> bool,
+    {
+        attempt <= self.retries && (self.predicate)(error)
+    }
+}
+
+impl<E, D, F> RetryPolicy<E> for Backoff<D, F>
+where
+    D: FnMut(u32),
+    F: FnMut(&E) -> bool,
+{
+    fn retry(&mut self, attempt: u32, error: &E) -> bool {
+        if !self.should_retry(attempt, error) {
+            return false;
+        }
+        let backoff = self.backoff_us(attempt);
+        (self.delay)(backoff);
+        true
+    }
+}
+
+/// Wrapper of an interface retrying the failed reads and writes according to the policy `P`.
+/// Each read and write is retried on its own, e.g. the read and the write of an edit.
+/// A failed write of a protected register is retried with its unlock sequence, since the device may have consumed the key.
+/// A FIFO read is retried as a whole, the values popped by a failed burst are lost.
+#[derive(Debug)]
+pub struct RetryInterface<I, P> {
+    iface: I,
+    policy: P,
+}
+
+impl<I, P> RetryInterface<I, P> {
+    /// Creates the wrapper of the interface with the retry policy
+    pub const fn new(iface: I, policy: P) -> Self {
+        Self { iface, policy }
+    }
+
+    /// Returns a reference to the inner interface
+    pub const fn inner(&self) -> &I {
+        &self.iface
+    }
+
+    /// Returns a mutable reference to the inner interface
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iface
+    }
+
+    /// Returns a mutable reference to the retry policy
+    pub fn policy_mut(&mut self) -> &mut P {
+        &mut self.policy
+    }
+
+    /// Returns a mutable reference to the inner interface and the retry policy
+    pub fn parts_mut(&mut self) -> (&mut I, &mut P) {
+        (&mut self.iface, &mut self.policy)
+    }
+
+    /// Returns the inner interface and the retry policy
+    pub fn release(self) -> (I, P) {
+        (self.iface, self.policy)
+    }
+}
+
+impl<I, P, R, A> RegisterInterface<R, A> for RetryInterface<I, P>
+where
+    R: Register<Address = A>,
+    R::Protection: Unlock<I, I::Error>,
+    I: RegisterInterface<R, A>,
+    P: RetryPolicy<I::Error>,
+{
+    type Error = I::Error;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let mut attempt = 0;
+        loop {
+            match self.iface.read_register() {
+                Ok(register) => return Ok(register),
+                Err(err) => {
+                    attempt += 1;
+                    if !self.policy.retry(attempt, &err) {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        // The register is unlocked by the caller before the first attempt, the retries unlock it again
+        let mut attempt = 0;
+        loop {
+            let result = match attempt {
+                0 => self.iface.write_register(register),
+                _ => R::Protection::unlock(&mut self.iface)
+                    .and_then(|()| self.iface.write_register(register)),
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    attempt += 1;
+                    if !self.policy.retry(attempt, &err) {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+
+    fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        let mut attempt = 0;
+        loop {
+            match self.iface.read_fifo_register(values) {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    attempt += 1;
+                    if !self.policy.retry(attempt, &err) {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+}
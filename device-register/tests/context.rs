@@ -0,0 +1,72 @@
+#[path = "./common.rs"]
+mod common;
+
+use common::{DeviceDriver, DeviceError};
+use device_register::*;
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = "common::REGISTER1")]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = DeviceError;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let bytes = self.registers.get(&R::ADDRESS).ok_or(DeviceError::Get)?;
+        let reg = u16::from_be_bytes(*bytes);
+        Ok(reg.into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let bytes: u16 = register.clone().into();
+        self.registers.insert(R::ADDRESS, bytes.to_be_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn access_error() {
+    let mut device = Context::new(DeviceDriver::new());
+    device.inner_mut().registers.clear();
+
+    let err = device.read::<Config, _>().unwrap_err();
+    assert_eq!(
+        err,
+        AccessError {
+            register: "Config",
+            address: 0x01,
+            operation: Operation::Read,
+            error: RegisterError::Bus(DeviceError::Get),
+        }
+    );
+    let err = device.edit(|r: &mut Config| r.0 += 1).unwrap_err();
+    assert_eq!(err.operation, Operation::Edit);
+
+    device.write(Config(0x42)).unwrap();
+    device.edit(|r: &mut Config| r.0 += 1).unwrap();
+    let config: Config = device.read().unwrap();
+    assert_eq!(config.0, 0x43);
+}
+
+#[test]
+fn display() {
+    let err = AccessError::new::<Config>(Operation::Write, "nack");
+    assert_eq!(
+        err.to_string(),
+        "failed to write register Config at 0x1: nack"
+    );
+}
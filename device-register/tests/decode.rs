@@ -0,0 +1,155 @@
+#[path = "./common.rs"]
+mod common;
+
+use common::{DeviceDriver, DeviceError};
+use device_register::*;
+
+/// Conversion mode, the value 2 is reserved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FieldEnum)]
+pub enum Mode {
+    Continuous,
+    Shutdown,
+    OneShot = 3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RWRegister)]
+#[register(addr = "common::REGISTER1", raw = "u16")]
+#[register(fields(MODE(offset = 10, width = 2, enum = "Mode")))]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+// The most significant bit is reserved and must be cleared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RORegister)]
+#[register(addr = "common::REGISTER2", try_from_raw)]
+pub struct Status(pub u16);
+impl From<Status> for u16 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Status {
+    fn from(val: u16) -> Self {
+        Status(val)
+    }
+}
+impl TryFromRaw for Status {
+    fn try_from_raw(register: Self) -> Result<Self, InvalidValue> {
+        match register.0 >> 15 {
+            0 => Ok(register),
+            bits => Err(InvalidValue {
+                register: Status::NAME,
+                field: "RESERVED",
+                bits: bits as u64,
+            }),
+        }
+    }
+}
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = DeviceError;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let bytes = self.registers.get(&R::ADDRESS).ok_or(DeviceError::Get)?;
+        let reg = u16::from_be_bytes(*bytes);
+        Ok(reg.into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let bytes: u16 = register.clone().into();
+        self.registers.insert(R::ADDRESS, bytes.to_be_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn decode_error() {
+    let mut device = DeviceDriver::new();
+    device
+        .registers
+        .insert(common::REGISTER1, 0x0800_u16.to_be_bytes());
+
+    let invalid = InvalidValue {
+        register: "Config",
+        field: "MODE",
+        bits: 2,
+    };
+    let res: Result<Config, _> = device.read();
+    assert_eq!(res, Err(RegisterError::Decode(invalid)));
+
+    // The register is not written back if the decoding fails
+    let res = device.edit(|r: &mut Config| r.set_mode(Mode::Shutdown));
+    assert_eq!(res, Err(RegisterError::Decode(invalid)));
+    assert_eq!(
+        device.registers.get(&common::REGISTER1).unwrap(),
+        &0x0800_u16.to_be_bytes()
+    );
+
+    device
+        .registers
+        .insert(common::REGISTER2, 0x8000_u16.to_be_bytes());
+    let res: Result<Status, _> = device.read();
+    assert!(matches!(res, Err(RegisterError::Decode(err)) if err.field == "RESERVED"));
+}
+
+#[test]
+fn bus_error() {
+    let mut device = DeviceDriver::new();
+    device.registers.clear();
+
+    let res: Result<Config, _> = device.read();
+    assert_eq!(res, Err(RegisterError::Bus(DeviceError::Get)));
+
+    device.write(Config(0x0400)).unwrap();
+    let config: Config = device.read().unwrap();
+    assert_eq!(config.mode(), Ok(Mode::Shutdown));
+}
+
+// A register implemented manually accepts every value, without implementing `TryFromRaw`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Manual(pub u16);
+impl Register for Manual {
+    type Address = u8;
+    type Protection = Unprotected;
+    const ADDRESS: Self::Address = common::REGISTER2;
+}
+impl ReadableRegister for Manual {}
+impl EditableRegister for Manual {}
+impl From<Manual> for u16 {
+    fn from(val: Manual) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Manual {
+    fn from(val: u16) -> Self {
+        Manual(val)
+    }
+}
+
+#[test]
+fn manual_register() {
+    let mut device = DeviceDriver::new();
+    device
+        .registers
+        .insert(common::REGISTER2, 0x8000_u16.to_be_bytes());
+
+    let manual: Manual = device.read().unwrap();
+    assert_eq!(manual, Manual(0x8000));
+    device.edit(|r: &mut Manual| r.0 |= 1).unwrap();
+    assert_eq!(
+        device.registers.get(&common::REGISTER2).unwrap(),
+        &0x8001_u16.to_be_bytes()
+    );
+}
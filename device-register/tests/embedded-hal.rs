@@ -0,0 +1,176 @@
+use device_register::{hal::*, *};
+use embedded_hal_mock::eh1::{
+    i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    spi::{Mock as SpiMock, Transaction as SpiTransaction},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RWRegister)]
+#[register(addr = 0x01, raw = "u16")]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RORegister)]
+#[register(addr = 0x0102, ty = "u16", raw = "u8")]
+pub struct Id(pub u8);
+impl From<Id> for u8 {
+    fn from(val: Id) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Id {
+    fn from(val: u8) -> Self {
+        Id(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RORegister)]
+#[register(addr = 0x3F, raw = "u16", fifo)]
+pub struct Sample(pub u16);
+impl From<Sample> for u16 {
+    fn from(val: Sample) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Sample {
+    fn from(val: u16) -> Self {
+        Sample(val)
+    }
+}
+
+#[test]
+fn i2c() {
+    let expectations = [
+        I2cTransaction::write_read(0x48, vec![0x01], vec![0x12, 0x34]),
+        I2cTransaction::write(0x48, vec![0x01, 0x12, 0x35]),
+        I2cTransaction::write_read(0x49, vec![0x01, 0x02], vec![0x42]),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut device = Device::new(I2cBus::new(i2c.clone()), 0x48);
+
+    device.edit(|r: &mut Config| r.0 += 1).unwrap();
+    let (bus, _) = device.release();
+    let mut other = Device::new(bus, 0x49);
+    let id: Id = other.read().unwrap();
+    assert_eq!(id, Id(0x42));
+
+    i2c.done();
+}
+
+#[test]
+fn spi() {
+    let expectations = [
+        SpiTransaction::transaction_start(),
+        SpiTransaction::transfer_in_place(vec![0x81, 0x00, 0x00], vec![0x00, 0x12, 0x34]),
+        SpiTransaction::transaction_end(),
+        SpiTransaction::transaction_start(),
+        SpiTransaction::write_vec(vec![0x01, 0x12, 0x35]),
+        SpiTransaction::transaction_end(),
+        SpiTransaction::transaction_start(),
+        SpiTransaction::transfer_in_place(vec![0x01, 0x02, 0x00], vec![0x00, 0x00, 0x42]),
+        SpiTransaction::transaction_end(),
+    ];
+    let mut spi = SpiMock::new(&expectations);
+    let mut device = SpiInterface::new(spi.clone());
+
+    device.edit(|r: &mut Config| r.0 += 1).unwrap();
+    let mut device = SpiInterface::new(device.into_inner()).with_flags(0x00, 0x80);
+    let id: Id = device.read().unwrap();
+    assert_eq!(id, Id(0x42));
+
+    spi.done();
+}
+
+#[test]
+fn i2c_fifo() {
+    // The 20 samples of 2 bytes are split in transfers of 32 bytes
+    let bytes: Vec<u8> = (0..20_u16).flat_map(u16::to_be_bytes).collect();
+    let expectations = [
+        I2cTransaction::write_read(0x48, vec![0x3F], bytes[..32].to_vec()),
+        I2cTransaction::write_read(0x48, vec![0x3F], bytes[32..].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut device = Device::new(I2cBus::new(i2c.clone()), 0x48);
+
+    let mut samples = [Sample(0); 20];
+    device.read_fifo(&mut samples).unwrap();
+    assert_eq!(samples, core::array::from_fn(|i| Sample(i as u16)));
+
+    i2c.done();
+}
+
+#[test]
+fn spi_fifo() {
+    let expectations = [
+        SpiTransaction::transaction_start(),
+        SpiTransaction::write_vec(vec![0xBF]),
+        SpiTransaction::read_vec(vec![0x00, 0x01, 0x00, 0x02, 0x00, 0x03]),
+        SpiTransaction::transaction_end(),
+    ];
+    let mut spi = SpiMock::new(&expectations);
+    let mut device = SpiInterface::new(spi.clone());
+
+    let mut samples = [Sample(0); 3];
+    device.read_fifo(&mut samples).unwrap();
+    assert_eq!(samples, [Sample(1), Sample(2), Sample(3)]);
+
+    spi.done();
+}
+
+#[test]
+fn i2c_raw() {
+    let expectations = [
+        I2cTransaction::write_read(0x48, vec![0x01, 0x02], vec![0x12, 0x34]),
+        I2cTransaction::write(0x48, vec![0x01, 0x02, 0x12, 0x35]),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut device = Device::new(I2cBus::new(i2c.clone()).with_address_size(2), 0x48);
+
+    let mut buf = [0; 2];
+    assert_eq!(device.read_raw(0x0102, &mut buf), Ok(2));
+    assert_eq!(buf, [0x12, 0x34]);
+    device.write_raw(0x0102, &[0x12, 0x35]).unwrap();
+    assert_eq!(
+        device.read_raw(0x010000, &mut buf),
+        Err(RawAccessError::UnknownAddress(0x010000))
+    );
+
+    i2c.done();
+}
+
+#[test]
+fn spi_raw() {
+    let expectations = [
+        SpiTransaction::transaction_start(),
+        SpiTransaction::write_vec(vec![0x81]),
+        SpiTransaction::read_vec(vec![0x12, 0x34]),
+        SpiTransaction::transaction_end(),
+        SpiTransaction::transaction_start(),
+        SpiTransaction::write_vec(vec![0x01, 0x12, 0x35]),
+        SpiTransaction::transaction_end(),
+    ];
+    let mut spi = SpiMock::new(&expectations);
+    let mut device = SpiInterface::new(spi.clone());
+
+    let mut buf = [0; 2];
+    assert_eq!(device.read_raw(0x01, &mut buf), Ok(2));
+    assert_eq!(buf, [0x12, 0x34]);
+    device.write_raw(0x01, &[0x12, 0x35]).unwrap();
+    assert_eq!(
+        device.write_raw(0x01, &[0; 32]),
+        Err(RawAccessError::Size {
+            expected: 31,
+            actual: 32
+        })
+    );
+
+    spi.done();
+}
@@ -0,0 +1,93 @@
+use device_register::*;
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = "0x00", read_side_effects)]
+pub struct Status(pub u16);
+impl From<Status> for u16 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Status {
+    fn from(val: u16) -> Self {
+        Status(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RERegister)]
+#[register(addr = "0x10")]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+// Manual register, its permissions are unknown
+#[derive(Debug, Clone, Copy)]
+pub struct Legacy(pub u16);
+impl Register for Legacy {
+    type Address = u8;
+    type Protection = Unprotected;
+    const ADDRESS: Self::Address = 0x20;
+    const NAME: &'static str = "Legacy";
+}
+impl From<Legacy> for u16 {
+    fn from(val: Legacy) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Legacy {
+    fn from(val: u16) -> Self {
+        Legacy(val)
+    }
+}
+
+#[derive(RegisterMap)]
+#[register_map(raw = "u16", registers(Status, Config, Legacy))]
+pub struct DeviceMap;
+
+#[test]
+fn export_json() {
+    let mut json = String::new();
+    export::json::<DeviceMap, _>(&mut json).unwrap();
+    assert_eq!(
+        json,
+        concat!(
+            r#"{"name":"DeviceMap","size":16,"registers":["#,
+            r#"{"name":"Status","address":0,"access":"ro","read_side_effects":true},"#,
+            r#"{"name":"Config","address":16,"access":"re","read_side_effects":false},"#,
+            r#"{"name":"Legacy","address":32,"read_side_effects":false}"#,
+            r#"]}"#
+        )
+    );
+}
+
+#[test]
+fn export_svd() {
+    let mut svd = String::new();
+    export::svd::<DeviceMap, _>(&mut svd).unwrap();
+    assert!(svd.contains("<name>DeviceMap</name>"));
+    assert!(svd.contains(
+        "<name>Status</name>\n          \
+         <addressOffset>0x0</addressOffset>\n          \
+         <access>read-only</access>\n          \
+         <readAction>modify</readAction>"
+    ));
+    assert!(svd.contains(
+        "<name>Config</name>\n          \
+         <addressOffset>0x10</addressOffset>\n          \
+         <access>read-write</access>\n        \
+         </register>"
+    ));
+    assert!(svd.contains(
+        "<name>Legacy</name>\n          \
+         <addressOffset>0x20</addressOffset>\n        \
+         </register>"
+    ));
+}
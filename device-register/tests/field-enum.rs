@@ -0,0 +1,116 @@
+#![deny(warnings)]
+
+use device_register::*;
+
+/// Conversion mode, the value 2 is reserved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FieldEnum)]
+pub enum Mode {
+    Continuous,
+    Shutdown,
+    OneShot = 3,
+}
+
+/// Averaging of the conversions, every value is valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FieldEnum)]
+#[field_enum(width = 2)]
+pub enum Averaging {
+    None,
+    Eight,
+    ThirtyTwo,
+    SixtyFour,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RWRegister)]
+#[register(addr = 0x01, raw = "u16")]
+#[register(fields(
+    MODE(offset = 10, width = 2, enum = "Mode"),
+    AVG(offset = 5, width = 2, enum = "Averaging"),
+    DataReady(offset = 13, access = "ro"),
+))]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[test]
+fn field_enum() {
+    assert_eq!(<Mode as FieldEnum>::WIDTH, 2);
+    assert_eq!(Mode::OneShot.bits(), 3);
+
+    let mut config = Config(0x0220);
+    assert_eq!(config.mode(), Ok(Mode::Continuous));
+    assert_eq!(config.avg(), Averaging::Eight);
+
+    config.set_mode(Mode::OneShot);
+    config.set_avg(Averaging::SixtyFour);
+    assert_eq!(config.0, 0x0E60);
+    assert_eq!(config.mode(), Ok(Mode::OneShot));
+    assert_eq!(config.avg(), Averaging::SixtyFour);
+}
+
+#[test]
+fn invalid_value() {
+    let config = Config(0x0800);
+    let err = config.mode().unwrap_err();
+    assert_eq!(
+        err,
+        InvalidValue {
+            register: "Config",
+            field: "MODE",
+            bits: 2
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid value 0x2 for field MODE of register Config"
+    );
+}
+
+#[test]
+fn try_from_raw_value() {
+    assert_eq!(Mode::try_from(3u8), Ok(Mode::OneShot));
+    assert_eq!(Mode::try_from(2u8), Err(2));
+    // A complete enumeration still rejects the values beyond its width
+    assert_eq!(Averaging::try_from(1u8), Ok(Averaging::Eight));
+    assert_eq!(Averaging::try_from(4u8), Err(4));
+}
+
+/// Enable of the output, named after a keyword
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FieldEnum)]
+pub enum Output {
+    Disabled,
+    Enabled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RWRegister)]
+#[register(addr = 0x02, raw = "u8")]
+#[register(fields(MOD(offset = 0, enum = "Output"), r#type(offset = 1, enum = "Output")))]
+pub struct Control(pub u8);
+impl From<Control> for u8 {
+    fn from(val: Control) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Control {
+    fn from(val: u8) -> Self {
+        Control(val)
+    }
+}
+
+#[test]
+fn keyword_accessors() {
+    let mut control = Control(0x01);
+    assert_eq!(control.r#mod(), Output::Enabled);
+    assert_eq!(control.r#type(), Output::Disabled);
+
+    control.set_mod(Output::Disabled);
+    control.set_type(Output::Enabled);
+    assert_eq!(control.0, 0x02);
+}
@@ -0,0 +1,71 @@
+use std::collections::{HashMap, VecDeque};
+
+use device_register::*;
+
+// Mock of a device whose FIFOs are drained in a single transfer
+#[derive(Default)]
+pub struct DeviceDriver {
+    pub fifos: HashMap<u8, VecDeque<u16>>,
+    pub transfers: usize,
+}
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.transfers += 1;
+        let fifo = self.fifos.get_mut(&R::ADDRESS).ok_or(())?;
+        fifo.pop_front().map(Into::into).ok_or(())
+    }
+
+    fn write_register(&mut self, _register: &R) -> Result<(), Self::Error> {
+        Err(())
+    }
+
+    fn read_fifo_register(&mut self, values: &mut [R]) -> Result<(), Self::Error> {
+        self.transfers += 1;
+        let fifo = self.fifos.get_mut(&R::ADDRESS).ok_or(())?;
+        if fifo.len() < values.len() {
+            return Err(());
+        }
+        for value in values {
+            *value = fifo.pop_front().unwrap().into();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RORegister)]
+#[register(addr = 0x3F, fifo)]
+pub struct Sample(pub u16);
+impl From<Sample> for u16 {
+    fn from(val: Sample) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Sample {
+    fn from(val: u16) -> Self {
+        Sample(val)
+    }
+}
+
+#[test]
+fn read_fifo() {
+    let mut device = DeviceDriver::default();
+    device.fifos.insert(0x3F, VecDeque::from([1, 2, 3, 4, 5]));
+
+    let mut samples = [Sample(0); 4];
+    device.read_fifo(&mut samples).unwrap();
+
+    assert_eq!(samples, [Sample(1), Sample(2), Sample(3), Sample(4)]);
+    assert_eq!(device.transfers, 1);
+    assert_eq!(device.fifos[&0x3F], [5]);
+
+    // Skipped by the tools reading the whole device
+    let info = RegisterInfo::<u8, u16>::new::<Sample>();
+    assert!(info.read_side_effects);
+}
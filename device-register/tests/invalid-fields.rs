@@ -0,0 +1,5 @@
+#[test]
+fn invalid_fields() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -1,4 +1,9 @@
-#![allow(clippy::identity_op, unused_braces)]
+#![allow(
+    clippy::identity_op,
+    clippy::new_without_default,
+    unused_braces,
+    unused_parens
+)]
 
 #[path = "./common.rs"]
 mod common;
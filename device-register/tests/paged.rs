@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use device_register::*;
+
+const PAGE_SELECT: u8 = 0x7F;
+
+// Mock of a device where the registers depend on the selected page
+#[derive(Default)]
+pub struct PagedDevice {
+    pub page: u8,
+    pub selects: usize,
+    pub registers: HashMap<(u8, u8), u16>,
+}
+
+impl<R> RegisterInterface<R, u8> for PagedDevice
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = ();
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let value = self.registers.get(&(self.page, R::ADDRESS)).ok_or(())?;
+        Ok((*value).into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let value: u16 = register.clone().into();
+        if R::ADDRESS == PAGE_SELECT {
+            self.page = value as u8;
+            self.selects += 1;
+        } else {
+            self.registers.insert((self.page, R::ADDRESS), value);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = "PAGE_SELECT")]
+pub struct PageSelect(pub u16);
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x10, page = 0)]
+pub struct Config(pub u16);
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x10, page = 2)]
+pub struct Threshold(pub u16);
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x20)]
+pub struct Status(pub u16);
+
+macro_rules! impl_raw {
+    ($($reg:ident),*) => {
+        $(
+            impl From<$reg> for u16 {
+                fn from(val: $reg) -> Self {
+                    val.0
+                }
+            }
+            impl From<u16> for $reg {
+                fn from(val: u16) -> Self {
+                    $reg(val)
+                }
+            }
+        )*
+    };
+}
+impl_raw!(PageSelect, Config, Threshold, Status);
+
+impl From<u8> for PageSelect {
+    fn from(page: u8) -> Self {
+        PageSelect(page.into())
+    }
+}
+
+#[test]
+fn paged_registers() {
+    assert_eq!(Config::PAGE, Some(0));
+    assert_eq!(Threshold::PAGE, Some(2));
+    assert_eq!(Status::PAGE, None);
+
+    let mut device = Paged::<_, PageSelect>::new(PagedDevice::default());
+    device.write(Config(0x42)).unwrap();
+    device.write(Threshold(0x45)).unwrap();
+    device.edit(|r: &mut Threshold| r.0 += 1).unwrap();
+    assert_eq!(device.page(), Some(2));
+
+    // Unpaged registers are accessed on the current page
+    device.write(Status(0x01)).unwrap();
+    assert_eq!(device.page(), Some(2));
+
+    let config: Config = device.read().unwrap();
+    assert_eq!(config.0, 0x42);
+
+    let device = device.into_inner();
+    assert_eq!(device.selects, 3);
+    assert_eq!(device.registers.get(&(0, 0x10)), Some(&0x42));
+    assert_eq!(device.registers.get(&(2, 0x10)), Some(&0x46));
+    assert_eq!(device.registers.get(&(2, 0x20)), Some(&0x01));
+
+    // The page is selected again after invalidating it
+    let mut device = Paged::<_, PageSelect>::with_page(device, 0);
+    let _: Config = device.read().unwrap();
+    device.invalidate();
+    let _: Config = device.read().unwrap();
+    assert_eq!(device.inner().selects, 4);
+}
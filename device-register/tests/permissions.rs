@@ -0,0 +1,52 @@
+use device_register::*;
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = "0x01")]
+pub struct ReadOnly(pub u16);
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = "0x01")]
+pub struct WriteOnly(pub u16);
+
+#[derive(Debug, Clone, Copy, EORegister)]
+#[register(addr = "0x01")]
+pub struct EditOnly(pub u16);
+
+#[derive(Debug, Clone, Copy, RERegister)]
+#[register(addr = "0x01")]
+pub struct ReadEdit(pub u16);
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = "0x01")]
+pub struct ReadWrite(pub u16);
+
+// Register implemented manually, without the optional metadata
+pub struct Manual;
+impl Register for Manual {
+    type Address = u8;
+    type Protection = Unprotected;
+    const ADDRESS: Self::Address = 0x01;
+}
+
+#[test]
+fn derived_permissions() {
+    assert_eq!(ReadOnly::PERMISSIONS, Permissions::READ);
+    assert_eq!(WriteOnly::PERMISSIONS, Permissions::WRITE);
+    assert_eq!(EditOnly::PERMISSIONS, Permissions::EDIT);
+    assert_eq!(ReadEdit::PERMISSIONS, Permissions::READ | Permissions::EDIT);
+    assert_eq!(ReadWrite::PERMISSIONS, Permissions::ALL);
+
+    assert!(ReadOnly::PERMISSIONS.is_readable());
+    assert!(!ReadOnly::PERMISSIONS.is_writable());
+    assert!(!WriteOnly::PERMISSIONS.is_readable());
+    assert!(ReadEdit::PERMISSIONS.contains(Permissions::EDIT));
+    assert!(!ReadEdit::PERMISSIONS.contains(Permissions::WRITE | Permissions::EDIT));
+    assert_eq!(Permissions::from_bits_truncate(0xFF), Permissions::ALL);
+}
+
+#[test]
+fn manual_permissions() {
+    assert_eq!(Manual::PERMISSIONS, Permissions::NONE);
+    assert!(!Manual::PERMISSIONS.is_readable());
+    assert_eq!(Manual::NAME, "");
+}
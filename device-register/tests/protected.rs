@@ -0,0 +1,107 @@
+use device_register::*;
+
+// Mock of a device that logs the writes
+#[derive(Default)]
+pub struct DeviceDriver {
+    pub writes: Vec<(u8, u8)>,
+}
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u8>,
+    u8: From<R>,
+{
+    type Error = ();
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        Ok(0.into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.writes.push((R::ADDRESS, register.clone().into()));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = 0x7E)]
+pub struct UnlockReg(pub u8);
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x10, protected(by = "UnlockReg", key = 0xA5))]
+pub struct Calibration(pub u8);
+
+#[derive(Debug, Clone, Copy, EORegister)]
+#[register(addr = 0x11, protected(by = "UnlockReg", key = "UnlockReg(0x5A)"))]
+pub struct Trim(pub u8);
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x20)]
+pub struct Config(pub u8);
+
+macro_rules! impl_raw {
+    ($($reg:ident),*) => {
+        $(
+            impl From<$reg> for u8 {
+                fn from(val: $reg) -> Self {
+                    val.0
+                }
+            }
+            impl From<u8> for $reg {
+                fn from(val: u8) -> Self {
+                    $reg(val)
+                }
+            }
+        )*
+    };
+}
+impl_raw!(UnlockReg, Calibration, Trim, Config);
+
+#[test]
+fn protected_registers() {
+    let mut device = DeviceDriver::default();
+
+    device.write(Calibration(0x42)).unwrap();
+    device.edit(|r: &mut Trim| r.0 = 0x01).unwrap();
+    device.write(Config(0x03)).unwrap();
+
+    assert_eq!(
+        device.writes,
+        [
+            (0x7E, 0xA5),
+            (0x10, 0x42),
+            (0x7E, 0x5A),
+            (0x11, 0x01),
+            (0x20, 0x03)
+        ]
+    );
+}
+
+#[derive(RegisterMap)]
+#[register_map(raw = "u8", registers(Calibration, Trim, Config))]
+pub struct DeviceMap;
+
+#[test]
+fn protected_raw_writes() {
+    let mut device = RawMap::<DeviceMap, _>::new(DeviceDriver::default());
+    device.write_raw(0x10, &[0x42]).unwrap();
+    device.write_raw(0x20, &[0x03]).unwrap();
+    assert_eq!(
+        device.into_inner().writes,
+        [(0x7E, 0xA5), (0x10, 0x42), (0x20, 0x03)]
+    );
+
+    let mut device = DeviceDriver::default();
+    let snapshot = Snapshot::<DeviceMap>::from_values([Some(0x42), Some(0x01), Some(0x03)]);
+    restore(&mut device, &snapshot).unwrap();
+    assert_eq!(
+        device.writes,
+        [
+            (0x7E, 0xA5),
+            (0x10, 0x42),
+            (0x7E, 0x5A),
+            (0x11, 0x01),
+            (0x20, 0x03)
+        ]
+    );
+}
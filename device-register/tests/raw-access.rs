@@ -0,0 +1,131 @@
+#[path = "./common.rs"]
+mod common;
+
+use common::{DeviceDriver, DeviceError};
+use device_register::*;
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = "common::REGISTER1")]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = "common::REGISTER2")]
+pub struct Status(pub u16);
+impl From<Status> for u16 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Status {
+    fn from(val: u16) -> Self {
+        Status(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, EORegister)]
+#[register(addr = 0x03)]
+pub struct Control(pub u16);
+impl From<Control> for u16 {
+    fn from(val: Control) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Control {
+    fn from(val: u16) -> Self {
+        Control(val)
+    }
+}
+
+#[derive(RegisterMap)]
+#[register_map(raw = "u16", registers(Config, Status, Control))]
+pub struct DeviceMap;
+
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = DeviceError;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let bytes = self.registers.get(&R::ADDRESS).ok_or(DeviceError::Get)?;
+        Ok(u16::from_be_bytes(*bytes).into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let value: u16 = register.clone().into();
+        self.registers.insert(R::ADDRESS, value.to_be_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn raw_access() {
+    let mut device = RawMap::<DeviceMap, _>::new(DeviceDriver::new());
+
+    // The register is chosen at runtime
+    let console: &mut dyn RawRegisterAccess<Error = RawAccessError<DeviceError>> = &mut device;
+    console.write_raw(0x01, &[0x12, 0x34]).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(console.read_raw(0x01, &mut buf), Ok(2));
+    assert_eq!(buf[..2], [0x12, 0x34]);
+
+    assert_eq!(
+        console.write_raw(0x02, &[0, 1]),
+        Err(RawAccessError::Denied("Status"))
+    );
+    assert_eq!(
+        console.write_raw(0x01, &[0]),
+        Err(RawAccessError::Size {
+            expected: 2,
+            actual: 1
+        })
+    );
+    assert_eq!(
+        console.read_raw(0x10, &mut buf),
+        Err(RawAccessError::UnknownAddress(0x10))
+    );
+
+    // Typed accesses are still available
+    let config: Config = device.read().unwrap();
+    assert_eq!(config.0, 0x1234);
+}
+
+#[test]
+fn raw_edit() {
+    let mut device = RawMap::<DeviceMap, _>::new(DeviceDriver::new());
+    device
+        .inner_mut()
+        .registers
+        .insert(0x03, 0x1200_u16.to_be_bytes());
+
+    // The edit only register can't be read or written on its own
+    let mut buf = [0; 2];
+    assert_eq!(
+        device.read_raw(0x03, &mut buf),
+        Err(RawAccessError::Denied("Control"))
+    );
+    assert_eq!(
+        device.write_raw(0x03, &[0, 1]),
+        Err(RawAccessError::Denied("Control"))
+    );
+    device
+        .edit_raw(0x03, &mut buf, &mut |bytes| bytes[1] = 0x34)
+        .unwrap();
+    assert_eq!(device.inner().registers.get(&0x03), Some(&[0x12, 0x34]));
+
+    assert_eq!(
+        device.edit_raw(0x02, &mut buf, &mut |_| {}),
+        Err(RawAccessError::Denied("Status"))
+    );
+}
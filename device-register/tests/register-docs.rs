@@ -0,0 +1,162 @@
+use device_register::*;
+
+/// Status of the device
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = "0x00", reset = 0x01, read_side_effects)]
+#[register(fields(READY(offset = 0), ERROR(offset = 2, doc = "An error | occured")))]
+pub struct Status(pub u8);
+impl From<Status> for u8 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Status {
+    fn from(val: u8) -> Self {
+        Status(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = "0x10", reset = "0x2A")]
+#[register(fields(
+    MODE(offset = 4, width = 3, doc = "Operating mode"),
+    RESET(offset = 0, access = "wo")
+))]
+pub struct Config(pub u8);
+impl From<Config> for u8 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Config {
+    fn from(val: u8) -> Self {
+        Config(val)
+    }
+}
+
+#[derive(RegisterMap)]
+#[register_map(raw = "u8", registers(Status, Config))]
+pub struct DeviceMap;
+
+#[test]
+fn register_metadata() {
+    assert_eq!(Status::DESCRIPTION, "Status of the device");
+    assert_eq!(Status::RESET, Some(0x01));
+    assert_eq!(Config::DESCRIPTION, "");
+    assert_eq!(Config::RESET, Some(0x2A));
+
+    // Fields are ordered by offset
+    assert_eq!(
+        Config::FIELDS,
+        &[
+            FieldInfo {
+                name: "RESET",
+                description: "",
+                offset: 0,
+                width: 1,
+                permissions: Permissions::WRITE,
+            },
+            FieldInfo {
+                name: "MODE",
+                description: "Operating mode",
+                offset: 4,
+                width: 3,
+                permissions: Permissions::ALL,
+            },
+        ]
+    );
+    assert_eq!(Config::FIELDS[1].mask(), 0b0111_0000);
+    assert_eq!(Config::FIELDS[1].extract(0x2A), 0b010);
+}
+
+#[test]
+fn register_docs_markdown() {
+    let mut markdown = String::new();
+    export::markdown::<DeviceMap, _>(&mut markdown).unwrap();
+    assert_eq!(
+        markdown,
+        r#"# DeviceMap
+
+| Register | Address | Access | Reset | Description |
+|---|---|---|---|---|
+| [Status](#status) | 0x00 | ro | 0x01 | Status of the device |
+| [Config](#config) | 0x10 | rw | 0x2a |  |
+
+## Status
+
+Status of the device
+
+Address: `0x00`, access: `ro`, reset: `0x01`, reading has side effects
+
+```text
+  7  6  5  4  3  2  1  0
+|              |a |  |b |
+a: ERROR
+b: READY
+```
+
+| Bits | Field | Access | Reset | Description |
+|---|---|---|---|---|
+| 2 | ERROR | ro | 0x0 | An error \| occured |
+| 0 | READY | ro | 0x1 |  |
+
+## Config
+
+Address: `0x10`, access: `rw`, reset: `0x2a`
+
+```text
+  7  6  5  4  3  2  1  0
+|  |  MODE  |        |a |
+a: RESET
+```
+
+| Bits | Field | Access | Reset | Description |
+|---|---|---|---|---|
+| 6:4 | MODE | rw | 0x2 | Operating mode |
+| 0 | RESET | wo | 0x0 |  |
+"#
+    );
+}
+
+#[test]
+fn field_beyond_raw_value() {
+    let field = FieldInfo {
+        name: "HIGH",
+        description: "",
+        offset: 64,
+        width: 4,
+        permissions: Permissions::ALL,
+    };
+    assert_eq!(field.mask(), 0);
+    assert_eq!(field.extract(u64::MAX), 0);
+
+    let field = FieldInfo {
+        offset: 62,
+        ..field
+    };
+    assert_eq!(field.mask(), 0b11 << 62);
+    assert_eq!(field.extract(u64::MAX), 0b11);
+}
+
+/// Control of the device
+#[register_doc]
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = "0x20", raw = "u8", reset = 0x80)]
+#[register(fields(
+    START(offset = 0),
+    GAIN(offset = 4, width = 4, doc = "Gain | of the amplifier")
+))]
+pub struct Control(pub u8);
+impl From<Control> for u8 {
+    fn from(val: Control) -> Self {
+        val.0
+    }
+}
+
+#[test]
+fn register_doc_layout() {
+    // The layout appended to the documentation is not part of the description
+    assert_eq!(Control::DESCRIPTION, "Control of the device");
+    assert_eq!(Control::RESET, Some(0x80));
+    assert_eq!(Control::FIELDS.len(), 2);
+}
@@ -0,0 +1,104 @@
+#[path = "./common.rs"]
+mod common;
+
+use common::{DeviceDriver, DeviceError};
+use device_register::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Address(pub u8);
+
+const STATUS: u8 = 0x03;
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = "Address(common::REGISTER1)", ty = "Address")]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = "Address(common::REGISTER2)", ty = "Address")]
+pub struct Command(pub u16);
+impl From<Command> for u16 {
+    fn from(val: Command) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Command {
+    fn from(val: u16) -> Self {
+        Command(val)
+    }
+}
+
+// Reading the status clears the pending flags
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = "Address(STATUS)", ty = "Address", read_side_effects)]
+pub struct Status(pub u16);
+impl From<Status> for u16 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Status {
+    fn from(val: u16) -> Self {
+        Status(val)
+    }
+}
+
+#[derive(RegisterMap)]
+#[register_map(ty = "Address", raw = "u16", registers(Config, Command, Status))]
+pub struct DeviceMap;
+
+// Implementation of the interface for this type of address
+impl<R> RegisterInterface<R, Address> for DeviceDriver
+where
+    R: Register<Address = Address> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = DeviceError;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let bytes = self.registers.get(&R::ADDRESS.0).ok_or(DeviceError::Get)?;
+        let reg = u16::from_be_bytes(*bytes);
+        Ok(reg.into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let bytes: u16 = register.clone().into();
+        self.registers.insert(R::ADDRESS.0, bytes.to_be_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn dump_register_map() {
+    let mut device = DeviceDriver::new();
+    device.write(Config(0x42)).unwrap();
+    device.write(Command(0x45)).unwrap();
+    device.registers.insert(STATUS, 0x01_u16.to_be_bytes());
+
+    let snapshot = dump::<DeviceMap, _>(&mut device).unwrap();
+
+    assert_eq!(snapshot.values(), &[Some(0x42), None, None]);
+    assert_eq!(snapshot.get("Config"), Some(&0x42));
+    assert_eq!(snapshot.get("Command"), None);
+
+    let entry = snapshot.entries().next().unwrap();
+    assert_eq!(entry.info.name, "Config");
+    assert_eq!(entry.info.address, Address(common::REGISTER1));
+    assert_eq!(entry.info.permissions, Permissions::ALL);
+
+    assert_eq!(
+        snapshot.to_string(),
+        "Config @ Address(1): 0x42 Config(66)\n\
+         Command @ Address(2): <skipped>\n\
+         Status @ Address(3): <skipped>\n"
+    );
+}
@@ -0,0 +1,184 @@
+#[path = "./common.rs"]
+mod common;
+
+use common::{DeviceDriver, DeviceError};
+use device_register::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Address(pub u8);
+
+const STATUS: u8 = 0x03;
+const CALIBRATION: u8 = 0x04;
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = "Address(common::REGISTER1)", ty = "Address")]
+#[register(fields(MODE(offset = 0, width = 4), GAIN(offset = 4, width = 4)))]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, WORegister)]
+#[register(addr = "Address(common::REGISTER2)", ty = "Address")]
+pub struct Command(pub u16);
+impl From<Command> for u16 {
+    fn from(val: Command) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Command {
+    fn from(val: u16) -> Self {
+        Command(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = "Address(STATUS)", ty = "Address")]
+pub struct Status(pub u16);
+impl From<Status> for u16 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Status {
+    fn from(val: u16) -> Self {
+        Status(val)
+    }
+}
+
+#[derive(Debug, Clone, Copy, EORegister)]
+#[register(addr = "Address(CALIBRATION)", ty = "Address")]
+pub struct Calibration(pub u16);
+impl From<Calibration> for u16 {
+    fn from(val: Calibration) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Calibration {
+    fn from(val: u16) -> Self {
+        Calibration(val)
+    }
+}
+
+#[derive(RegisterMap)]
+#[register_map(
+    ty = "Address",
+    raw = "u16",
+    registers(Config, Command, Status, Calibration)
+)]
+pub struct DeviceMap;
+
+// The calibration depends on the configuration, so it is restored after it
+#[derive(RegisterMap)]
+#[register_map(
+    ty = "Address",
+    raw = "u16",
+    registers(Calibration, Config),
+    restore_order(Calibration)
+)]
+pub struct OrderedMap;
+
+// Interface logging the addresses written
+#[derive(Default)]
+pub struct WriteLog {
+    pub device: DeviceDriver,
+    pub writes: Vec<u8>,
+}
+
+impl<R> RegisterInterface<R, Address> for WriteLog
+where
+    R: Register<Address = Address> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = DeviceError;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        self.device.read_register()
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        self.writes.push(R::ADDRESS.0);
+        self.device.write_register(register)
+    }
+}
+
+// Implementation of the interface for this type of address
+impl<R> RegisterInterface<R, Address> for DeviceDriver
+where
+    R: Register<Address = Address> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = DeviceError;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let bytes = self.registers.get(&R::ADDRESS.0).ok_or(DeviceError::Get)?;
+        let reg = u16::from_be_bytes(*bytes);
+        Ok(reg.into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let bytes: u16 = register.clone().into();
+        self.registers.insert(R::ADDRESS.0, bytes.to_be_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn diff_and_restore() {
+    let mut device = DeviceDriver::new();
+    device.registers.insert(STATUS, 0x01_u16.to_be_bytes());
+    device.registers.insert(CALIBRATION, 0x10_u16.to_be_bytes());
+    device.write(Config(0x42)).unwrap();
+    let saved = dump::<DeviceMap, _>(&mut device).unwrap();
+
+    // Simulate a brown-out
+    device.write(Config(0x00)).unwrap();
+    device.registers.insert(STATUS, 0x03_u16.to_be_bytes());
+    let current = dump::<DeviceMap, _>(&mut device).unwrap();
+
+    let mut diff = saved.diff(&current);
+    let config = diff.next().unwrap();
+    assert_eq!(config.info.name, "Config");
+    assert_eq!(config.changed_bits(), Some(0x42));
+    let fields: Vec<_> = config.changed_fields().map(|f| f.to_string()).collect();
+    assert_eq!(fields, ["MODE: 0x2 -> 0x0", "GAIN: 0x4 -> 0x0"]);
+    assert_eq!(
+        config.to_string(),
+        "Config @ Address(1): Config(66) -> Config(0)"
+    );
+    let status = diff.next().unwrap();
+    assert_eq!(status.info.name, "Status");
+    assert_eq!(status.changed_bits(), Some(0x02));
+    assert_eq!(status.changed_fields().count(), 0);
+    assert!(diff.next().is_none());
+
+    // The edit only calibration is not dumped but restored from its value in the snapshot
+    let mut values = saved.values().to_vec();
+    values[3] = Some(0x10);
+    let saved = Snapshot::<DeviceMap>::from_values(values.try_into().unwrap());
+    device.registers.insert(CALIBRATION, 0x00_u16.to_be_bytes());
+    restore(&mut device, &saved).unwrap();
+
+    // The read only status is not restored
+    let restored = dump::<DeviceMap, _>(&mut device).unwrap();
+    assert_eq!(restored.values(), &[Some(0x42), None, Some(0x03), None]);
+    assert_eq!(device.registers[&CALIBRATION], 0x10_u16.to_be_bytes());
+}
+
+#[test]
+fn restore_order() {
+    assert_eq!(DeviceMap::RESTORE_ORDER, &[] as &[usize]);
+    assert_eq!(OrderedMap::RESTORE_ORDER, &[0]);
+
+    let mut device = WriteLog::default();
+    let snapshot = Snapshot::<OrderedMap>::from_values([Some(0x10), Some(0x42)]);
+    restore(&mut device, &snapshot).unwrap();
+    assert_eq!(device.writes, [common::REGISTER1, CALIBRATION]);
+}
@@ -0,0 +1,134 @@
+#![allow(
+    clippy::identity_op,
+    clippy::new_without_default,
+    unused_braces,
+    unused_parens
+)]
+
+#[path = "./common.rs"]
+mod common;
+
+use common::{DeviceDriver, DeviceError};
+use device_register::*;
+use modular_bitfield::bitfield;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RWRegister)]
+#[register(addr = "common::REGISTER1", raw = "u16", serde)]
+pub struct Register1(pub u16);
+impl From<Register1> for u16 {
+    fn from(val: Register1) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Register1 {
+    fn from(val: u16) -> Self {
+        Register1(val)
+    }
+}
+
+#[bitfield]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RWRegister)]
+#[register(addr = "common::REGISTER2", raw = "u16", serde)]
+pub struct Register2 {
+    pub field1: u8,
+    pub field2: u8,
+}
+
+// Serialized as named fields by using serde directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RORegister, Serialize, Deserialize)]
+#[register(addr = "common::REGISTER1")]
+pub struct Register3 {
+    pub enabled: bool,
+    pub mode: u8,
+}
+
+// Has a raw representation but derives serde itself, without the `serde` attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RWRegister, Serialize, Deserialize)]
+#[register(addr = "common::REGISTER2", raw = "u16")]
+pub struct Register4 {
+    pub value: u16,
+}
+impl From<Register4> for u16 {
+    fn from(val: Register4) -> Self {
+        val.value
+    }
+}
+impl From<u16> for Register4 {
+    fn from(val: u16) -> Self {
+        Register4 { value: val }
+    }
+}
+
+#[derive(RegisterMap)]
+#[register_map(raw = "u16", registers(Register1, Register2))]
+pub struct DeviceMap;
+
+// Implementation of the interface for a u8
+impl<R> RegisterInterface<R, u8> for DeviceDriver
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type Error = DeviceError;
+
+    fn read_register(&mut self) -> Result<R, Self::Error> {
+        let bytes = self.registers.get(&(R::ADDRESS)).ok_or(DeviceError::Get)?;
+        let reg = u16::from_be_bytes(*bytes);
+        Ok(reg.into())
+    }
+
+    fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
+        let bytes: u16 = register.clone().into();
+        self.registers.insert(R::ADDRESS, bytes.to_be_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn serde_registers() {
+    let json = serde_json::to_string(&Register1(0x42)).unwrap();
+    assert_eq!(json, "66");
+    let reg: Register1 = serde_json::from_str(&json).unwrap();
+    assert_eq!(reg, Register1(0x42));
+
+    let reg2 = Register2::new().with_field1(0x01).with_field2(0x02);
+    let json = serde_json::to_string(&reg2).unwrap();
+    let reg: Register2 = serde_json::from_str(&json).unwrap();
+    assert_eq!(reg, reg2);
+
+    let reg3 = Register3 {
+        enabled: true,
+        mode: 2,
+    };
+    let json = serde_json::to_string(&reg3).unwrap();
+    assert_eq!(json, r#"{"enabled":true,"mode":2}"#);
+
+    let json = serde_json::to_string(&Register4 { value: 3 }).unwrap();
+    assert_eq!(json, r#"{"value":3}"#);
+}
+
+#[test]
+fn serde_snapshot() {
+    let mut device = DeviceDriver::new();
+    device.write(Register1(0x42)).unwrap();
+    device.write(Register2::from(0x0102)).unwrap();
+
+    let snapshot = dump::<DeviceMap, _>(&mut device).unwrap();
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert_eq!(json, r#"{"Register1":66,"Register2":258}"#);
+
+    let fixture: Snapshot<DeviceMap> = serde_json::from_str(r#"{"Register2":3}"#).unwrap();
+    assert_eq!(fixture.values(), &[None, Some(3)]);
+    restore(&mut device, &fixture).unwrap();
+    let reg: Register2 = device.read().unwrap();
+    assert_eq!(u16::from(reg), 3);
+
+    let err = serde_json::from_str::<Snapshot<DeviceMap>>(r#"{"Register3":3}"#).unwrap_err();
+    assert!(err.to_string().contains("unknown register `Register3`"));
+
+    let err = serde_json::from_str::<Snapshot<DeviceMap>>(r#"{"Register2":3,"Register2":4}"#)
+        .unwrap_err();
+    assert!(err.to_string().contains("duplicate field `Register2`"));
+}
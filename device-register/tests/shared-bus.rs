@@ -0,0 +1,67 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use device_register::*;
+
+// Mock of a bus with multiple devices, each selected by its address
+#[derive(Default)]
+pub struct Bus {
+    pub registers: HashMap<(u8, u8), u16>,
+}
+
+impl<R> BusInterface<R, u8> for Bus
+where
+    R: Register<Address = u8> + Clone + From<u16>,
+    u16: From<R>,
+{
+    type DeviceAddress = u8;
+    type Error = ();
+
+    fn read_register(&mut self, device: &u8) -> Result<R, Self::Error> {
+        let value = self.registers.get(&(*device, R::ADDRESS)).ok_or(())?;
+        Ok((*value).into())
+    }
+
+    fn write_register(&mut self, device: &u8, register: &R) -> Result<(), Self::Error> {
+        self.registers
+            .insert((*device, R::ADDRESS), register.clone().into());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, RWRegister)]
+#[register(addr = 0x01)]
+pub struct Config(pub u16);
+impl From<Config> for u16 {
+    fn from(val: Config) -> Self {
+        val.0
+    }
+}
+impl From<u16> for Config {
+    fn from(val: u16) -> Self {
+        Config(val)
+    }
+}
+
+#[test]
+fn shared_bus() {
+    let bus = RefCell::new(Bus::default());
+    let mut sensor1 = Device::new(&bus, 0x48);
+    let mut sensor2 = Device::new(&bus, 0x49);
+
+    sensor1.write(Config(0x42)).unwrap();
+    sensor2.write(Config(0x45)).unwrap();
+    sensor1.edit(|r: &mut Config| r.0 += 1).unwrap();
+
+    let config1: Config = sensor1.read().unwrap();
+    let config2: Config = sensor2.read().unwrap();
+    assert_eq!(config1.0, 0x43);
+    assert_eq!(config2.0, 0x45);
+    assert_eq!(*sensor2.address(), 0x49);
+    assert_eq!(bus.borrow().registers.len(), 2);
+
+    // The bus can also be borrowed mutably for a single device
+    let mut bus = bus.into_inner();
+    let mut sensor3 = Device::new(&mut bus, 0x4A);
+    sensor3.write(Config(0x01)).unwrap();
+    assert_eq!(bus.registers.get(&(0x4A, 0x01)), Some(&0x01));
+}
@@ -0,0 +1,17 @@
+use device_register::*;
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = "0x00", raw = "u8", fields(MODE(offset = 6, width = 4)))]
+pub struct Status(pub u8);
+impl From<Status> for u8 {
+    fn from(val: Status) -> Self {
+        val.0
+    }
+}
+impl From<u8> for Status {
+    fn from(val: u8) -> Self {
+        Status(val)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use device_register::*;
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = "0x00", fields(EN(offset = 0, width = 0)))]
+pub struct Status(pub u8);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use device_register::*;
+
+#[derive(Debug, Clone, Copy, RORegister)]
+#[register(addr = "0x00", fields(MODE(offset = 0, width = 4), GAIN(offset = 2, width = 2)))]
+pub struct Status(pub u8);
+
+fn main() {}
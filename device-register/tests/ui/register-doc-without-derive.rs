@@ -0,0 +1,7 @@
+use device_register::*;
+
+#[register_doc]
+#[register(addr = "0x01")]
+pub struct Status(pub u8);
+
+fn main() {}